@@ -56,6 +56,7 @@ this matters for 2 things:
 	Return(Return<'a,'b>),
 	Declare(Assign<'a,'b>),
 	Function(FuncDef<'a,'b>),
+	Import(Import<'a>),
 }
 
 //some argonomics
@@ -80,6 +81,16 @@ impl<'a, 'b> GrammerNode<'a, 'b> {
     }
 }
 
+impl<'a, 'b> GrammerNode<'a, 'b> {
+    //splits an expression node into its value and its error so it can be stored as a Value
+    pub fn into_value(self) -> (Option<Value<'a, 'b>>, Option<Box<UserSideError<'a>>>) {
+        match self.base {
+            GrammerNodeBase::Val(value) => (Some(value), self.error),
+            _ => (None, self.error),
+        }
+    }
+}
+
 impl<'a, 'b> From<GrammerNodeBase<'a, 'b>> for GrammerNode<'a, 'b> {
     fn from(base: GrammerNodeBase<'a, 'b>) -> Self {
         GrammerNode::new(base)
@@ -125,6 +136,7 @@ pub enum KeyWord<'a>{
 	
 	Cond(LocatedSpan<&'a str>),
 	Match(LocatedSpan<&'a str>),
+	For(LocatedSpan<&'a str>),
 }
 
 impl<'a> KeyWord<'a> {
@@ -138,7 +150,8 @@ impl<'a> KeyWord<'a> {
             | KeyWord::If(span)
             | KeyWord::Else(span)
             | KeyWord::Cond(span)
-            | KeyWord::Match(span) => *span,
+            | KeyWord::Match(span)
+            | KeyWord::For(span) => *span,
         }
     }
 }
@@ -149,15 +162,89 @@ pub enum Value<'a,'b>{
 	Nil(KeyWord<'a>),
 
 	Var(Varible<'a>),
+	Path(Path<'a>),
+	Member(Member<'a,'b>),
 	Paren(ParenExpr<'a,'b>),
 	Op(BinaryOpNode<'a,'b>),
+	Unary(UnaryOpNode<'a,'b>),
 	
 	Call(FunctionCall<'a,'b>),
 
 	If(If<'a,'b>),
 	Func(Lamda<'a,'b>),
-	// need to implement Match and Cond
+	Cond(Cond<'a,'b>),
+	Match(Match<'a,'b>),
+	For(Comprehension<'a,'b>),
+	Range(RangeExpr<'a,'b>),
+
+	Array(ListExpr<'a,'b>), //a function from an index to an element. out of bounds gives %out_of_bounds
+	Tuple(ListExpr<'a,'b>),
+	Map(MapExpr<'a,'b>),
+}
+
+//start..end and start..end:step. both ends are included and the step defaults to 1.
+//like an array it is a function from an index to an element but nothing is stored, its length is known up front
+#[derive(Debug, PartialEq)]
+pub struct RangeExpr<'a,'b>{
+	pub start: Box<GrammerNode<'a,'b>>,
+	pub dots: LocatedSpan<&'a str>, //..
+	pub end: Option<Box<GrammerNode<'a,'b>>>,
+	pub step: Option<RangeStep<'a,'b>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RangeStep<'a,'b>{
+	pub colon: LocatedSpan<&'a str>, //:
+	pub value: Option<Box<GrammerNode<'a,'b>>>,
+}
+
+//module::func keeps every segment so name resolution and imports can use it
+#[derive(Debug, PartialEq)]
+pub struct Path<'a>{
+	pub segments: Vec<LocatedSpan<&'a str>>,
+}
 
+//value.field
+#[derive(Debug, PartialEq)]
+pub struct Member<'a,'b>{
+	pub value: Box<GrammerNode<'a,'b>>,
+	pub dot: LocatedSpan<&'a str>,
+	pub field: Option<LocatedSpan<&'a str>>, //None if there was no name after the .
+}
+
+//[a, b] arrays, {a, b} tuples and the (a, b) of a call.
+//arrays are functions so arr(i) is just a FunctionCall
+#[derive(Debug, PartialEq)]
+pub struct ListExpr<'a,'b>{
+	pub start: Option<LocatedSpan<&'a str>>,
+	pub items: Vec<ListItem<'a,'b>>,
+	pub end: Option<LocatedSpan<&'a str>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ListItem<'a,'b>{
+	pub value: GrammerNode<'a,'b>,
+	pub first: LocatedSpan<&'a str>, //first and last tokens of the item for reporting
+	pub last: LocatedSpan<&'a str>,
+	pub ender: Option<LocatedSpan<&'a str>>, //,
+}
+
+//%{key => value} maps are functions from a key to its value. missing keys return %not_found
+#[derive(Debug, PartialEq)]
+pub struct MapExpr<'a,'b>{
+	pub percent: LocatedSpan<&'a str>, //%
+	pub start: Option<LocatedSpan<&'a str>>, //{
+	pub entries: Vec<MapEntry<'a,'b>>,
+	pub end: Option<LocatedSpan<&'a str>>, //}
+}
+
+//key => value. parsed like a cond arm but a key is never a condition
+#[derive(Debug, PartialEq)]
+pub struct MapEntry<'a,'b>{
+	pub key: Box<GrammerNode<'a,'b>>,
+	pub arrow: Option<LocatedSpan<&'a str>>, //=>
+	pub value: Option<Box<GrammerNode<'a,'b>>>,
+	pub ender: Option<LocatedSpan<&'a str>>, //,
 }
 
 #[derive(Debug, PartialEq)]
@@ -168,9 +255,9 @@ pub struct Varible<'a> {
 
 #[derive(Debug, PartialEq)]
 pub struct FunctionCall<'a,'b>{
-	pub piped : Option<Box<Value<'a,'b>>>,
+	pub piped : Option<Box<Value<'a,'b>>>, //x |> f(a) is f(x,a) so this goes before the arguments
 	pub func : Func<'a,'b>,
-	pub par : Box<ParenExpr<'a,'b>>,
+	pub args : ListExpr<'a,'b>,
 }
 
 //this is unified for implicit returns and explicit returns
@@ -181,6 +268,13 @@ pub struct Return<'a,'b>{
 	pub ender: Option<LocatedSpan<&'a str>>, //;
 }
 
+//-x and +x. a BinaryOpNode without a left side is only ever error recovery
+#[derive(Debug, PartialEq)]
+pub struct UnaryOpNode<'a, 'b> {
+    pub operator: SmallLexToken<'a>,
+    pub operand: Option<Box<GrammerNode<'a, 'b>>>, // None if missing
+}
+
 #[derive(Debug, PartialEq)]
 pub struct BinaryOpNode<'a, 'b> {
     pub left: Option<Box<GrammerNode<'a, 'b>>>, // Left-hand operand, might be `None` if missing
@@ -205,7 +299,7 @@ pub struct ParenExpr<'a,'b>{
 	pub end: Option<LocatedSpan<&'a str>>,//stays None untill we fined a closer (if we even find it)
 }
 
-//ifs and lamda have the exact same syntax... other than the keyword. shared logic here
+//(cond) {body} of an if. lamdas and defs take patterns in their () so they use PatternList instead
 #[derive(Debug, PartialEq)]
 pub struct Block<'a,'b>{ 
 	pub start: Box<ParenExpr<'a,'b>>, //note we use specifcly () braces here
@@ -222,27 +316,135 @@ pub struct If<'a,'b>{
 #[derive(Debug, PartialEq)]
 pub struct Else<'a,'b>{
 	pub keyword : KeyWord<'a>,
+	pub body: ParenExpr<'a,'b>, //for else if there are no braces and the body is the next if
+}
+
+//cond { a > 1 => x, _ => y } the first arm whose condition holds is returned
+#[derive(Debug, PartialEq)]
+pub struct Cond<'a,'b>{
+	pub keyword : KeyWord<'a>,
+	pub start: Option<LocatedSpan<&'a str>>, //{
+	pub arms: Vec<CondArm<'a,'b>>,
+	pub end: Option<LocatedSpan<&'a str>>, //}
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CondArm<'a,'b>{
+	pub condition: Box<GrammerNode<'a,'b>>,
+	pub arrow: Option<LocatedSpan<&'a str>>, //=>
+	pub body: Option<Box<GrammerNode<'a,'b>>>,
+	pub ender: Option<LocatedSpan<&'a str>>, //,
+}
+
+//match x { pattern if guard => body, ... } the first arm whose pattern fits (and guard holds) is returned
+#[derive(Debug, PartialEq)]
+pub struct Match<'a,'b>{
+	pub keyword : KeyWord<'a>,
+	pub value: Option<Box<GrammerNode<'a,'b>>>, //what we are matching on
+	pub start: Option<LocatedSpan<&'a str>>, //{
+	pub arms: Vec<MatchArm<'a,'b>>,
+	pub end: Option<LocatedSpan<&'a str>>, //}
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MatchArm<'a,'b>{
+	pub pattern: Pattern<'a>,
+	pub guard: Option<Guard<'a,'b>>,
+	pub arrow: Option<LocatedSpan<&'a str>>, //=>
+	pub body: Option<Box<GrammerNode<'a,'b>>>,
+	pub ender: Option<LocatedSpan<&'a str>>, //,
+}
+
+//for (x <- xs, y <- ys, x < y) { x * y } runs the body for every combination the generators give
+//that passes all the filters and collects the results into an array. this is the only way to loop
+#[derive(Debug, PartialEq)]
+pub struct Comprehension<'a,'b>{
+	pub keyword : KeyWord<'a>,
+	pub start: Option<LocatedSpan<&'a str>>, //(
+	pub clauses: Vec<ForClause<'a,'b>>, //in order, later clauses see the bindings of earlier generators
+	pub end: Option<LocatedSpan<&'a str>>, //)
 	pub body: ParenExpr<'a,'b>,
 }
 
-//add match and cond
+#[derive(Debug, PartialEq)]
+pub enum ForClause<'a,'b>{
+	Generator(Generator<'a,'b>),
+	Filter(GrammerNode<'a,'b>),
+}
+
+//pattern <- source. elements that do not fit the pattern are skipped
+#[derive(Debug, PartialEq)]
+pub struct Generator<'a,'b>{
+	pub pattern: Pattern<'a>,
+	pub arrow: LocatedSpan<&'a str>, //<-
+	pub source: Option<Box<GrammerNode<'a,'b>>>,
+}
+
+//the if cond part after a pattern
+#[derive(Debug, PartialEq)]
+pub struct Guard<'a,'b>{
+	pub keyword : KeyWord<'a>,
+	pub condition: Option<Box<GrammerNode<'a,'b>>>,
+}
+
+//patterns are kept apart from values since they bind names instead of using them.
+//they never hold unprocessed tokens so there is no 'b here
+#[derive(Debug, PartialEq)]
+pub enum Pattern<'a>{
+	Literal(SmallLexToken<'a>), //ints floats strings and atoms
+	Nil(KeyWord<'a>),
+	Wildcard(LocatedSpan<&'a str>), //_
+	Bind(Varible<'a>),
+	Array(PatternList<'a>), //[a, b]
+	Tuple(PatternList<'a>), //{a, b}
+	Invalid(LocatedSpan<&'a str>), //the first token of something that is not a pattern. always comes with an error
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PatternList<'a>{
+	pub start: Option<LocatedSpan<&'a str>>,
+	pub items: Vec<Pattern<'a>>,
+	pub end: Option<LocatedSpan<&'a str>>,
+}
 
 #[derive(Debug, PartialEq)]
 pub enum Func<'a,'b>{
 	Defed(&'b FuncDef<'a,'b>),
 	Vared(Varible<'a>),
+	Pathed(Path<'a>),
 	Anon(Lamda<'a,'b>),
+	Computed(Box<GrammerNode<'a,'b>>),//anything else that evaluates to a function like f(1)(2)
+	Concat(LocatedSpan<&'a str>),//"a #{b}" turns every argument into a string and joins them. the span is the start of the string
 }
 
+//fn(params) {body}. the head is parsed by parse_params, the same as a def clause, so it takes patterns too
 #[derive(Debug, PartialEq)]
 pub struct Lamda<'a,'b>{
 	pub keyword : KeyWord<'a>,
-	pub body: Block<'a,'b>,
+	pub params: Box<PatternList<'a>>, //boxed to keep Value small
+	pub body: Box<ParenExpr<'a,'b>>,
 }
 
+//def fact(0) {1} def fact(n) if (n > 0) {...} is one function. clauses are tried in source order
 #[derive(Debug, PartialEq)]
 pub struct FuncDef<'a,'b>{
-	pub keyword : KeyWord<'a>,
 	pub name : Option<LocatedSpan<&'a str>>,
-	pub body: Block<'a,'b>,
-}
\ No newline at end of file
+	pub clauses : Vec<FuncClause<'a,'b>>, //never empty
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FuncClause<'a,'b>{
+	pub keyword : KeyWord<'a>,
+	pub name : Option<LocatedSpan<&'a str>>, //this clause's own name for reporting
+	pub params : PatternList<'a>, //the () head
+	pub guard : Option<Guard<'a,'b>>, //if (cond) between the head and the body
+	pub body : ParenExpr<'a,'b>,
+}
+//import a::b::c / import a::b as x / import a::b::{f, g}
+#[derive(Debug, PartialEq)]
+pub struct Import<'a>{
+	pub keyword : KeyWord<'a>,
+	pub path : Vec<LocatedSpan<&'a str>>, //the segments between the ::
+	pub alias : Option<LocatedSpan<&'a str>>,
+	pub items : Option<Vec<LocatedSpan<&'a str>>>, //None means the whole path is imported
+}
@@ -0,0 +1,195 @@
+use crate::ast::{GrammerNode,GrammerNodeBase,Value,Func,Lamda,Block,ParenExpr,ListExpr,ForClause};
+use crate::errors::UserSideError;
+
+/*
+errors live on the node closest to where they happened so a broken function still has all of its other parts.
+this gathers them back into a flat list in tree order for reporting.
+*/
+
+pub fn collect_errors<'a>(node:&GrammerNode<'a,'_>) -> Vec<UserSideError<'a>> {
+	let mut errors = Vec::new();
+	visit_node(node,&mut errors);
+	errors
+}
+
+fn push<'a>(error:&Option<Box<UserSideError<'a>>>,errors:&mut Vec<UserSideError<'a>>) {
+	match error.as_deref() {
+		Some(UserSideError::Compound(inner)) => errors.extend(inner.iter().cloned()),
+		Some(error) => errors.push(error.clone()),
+		None => {},
+	}
+}
+
+fn visit_node<'a>(node:&GrammerNode<'a,'_>,errors:&mut Vec<UserSideError<'a>>) {
+	push(&node.error,errors);
+	match &node.base {
+		GrammerNodeBase::Unprocessed(_)
+		| GrammerNodeBase::KeyWord(_)
+		| GrammerNodeBase::Import(_) => {},
+
+		GrammerNodeBase::Paren(paren) => visit_paren(paren,errors),
+		GrammerNodeBase::ControlBlock(block) => visit_block(block,errors),
+		GrammerNodeBase::Else(else_block) => visit_paren(&else_block.body,errors),
+		GrammerNodeBase::Sequence(nodes) => nodes.iter().for_each(|x| visit_node(x,errors)),
+		GrammerNodeBase::Val(value) => visit_value(value,errors),
+		GrammerNodeBase::Return(ret) => if let Some(value) = &ret.value {
+			visit_value(value,errors);
+		},
+		GrammerNodeBase::Declare(assign) => if let Some(value) = &assign.right {
+			visit_value(value,errors);
+		},
+		GrammerNodeBase::Function(def) => for clause in def.clauses.iter() {
+			if let Some(condition) = clause.guard.as_ref().and_then(|x| x.condition.as_ref()) {
+				visit_node(condition,errors);
+			}
+			visit_paren(&clause.body,errors);
+		},
+	}
+}
+
+fn visit_value<'a>(value:&Value<'a,'_>,errors:&mut Vec<UserSideError<'a>>) {
+	match value {
+		Value::Basic(_) | Value::Nil(_) | Value::Var(_) | Value::Path(_) => {},
+		Value::Member(member) => visit_node(&member.value,errors),
+		Value::Paren(paren) => visit_paren(paren,errors),
+		Value::Unary(op) => {
+			if let Some(operand) = &op.operand {
+				visit_node(operand,errors);
+			}
+		},
+		Value::Op(op) => {
+			if let Some(left) = &op.left {
+				visit_node(left,errors);
+			}
+			if let Some(right) = &op.right {
+				visit_node(right,errors);
+			}
+		},
+		Value::Call(call) => {
+			if let Some(piped) = &call.piped {
+				visit_value(piped,errors);
+			}
+			match &call.func {
+				Func::Defed(_) | Func::Vared(_) | Func::Pathed(_) | Func::Concat(_) => {},
+				Func::Anon(lamda) => visit_lamda(lamda,errors),
+				Func::Computed(node) => visit_node(node,errors),
+			}
+			visit_list(&call.args,errors);
+		},
+		Value::If(if_node) => {
+			visit_block(&if_node.body,errors);
+			if let Some(else_block) = &if_node.else_block {
+				visit_paren(&else_block.body,errors);
+			}
+		},
+		Value::Func(lamda) => visit_lamda(lamda,errors),
+		Value::Cond(cond) => for arm in cond.arms.iter() {
+			visit_node(&arm.condition,errors);
+			if let Some(body) = &arm.body {
+				visit_node(body,errors);
+			}
+		},
+		Value::Map(map) => for entry in map.entries.iter() {
+			visit_node(&entry.key,errors);
+			if let Some(value) = &entry.value {
+				visit_node(value,errors);
+			}
+		},
+		Value::Match(match_node) => {
+			if let Some(value) = &match_node.value {
+				visit_node(value,errors);
+			}
+			for arm in match_node.arms.iter() {
+				if let Some(condition) = arm.guard.as_ref().and_then(|x| x.condition.as_ref()) {
+					visit_node(condition,errors);
+				}
+				if let Some(body) = &arm.body {
+					visit_node(body,errors);
+				}
+			}
+		},
+		Value::Array(list) | Value::Tuple(list) => visit_list(list,errors),
+		Value::Range(range) => {
+			visit_node(&range.start,errors);
+			if let Some(end) = &range.end {
+				visit_node(end,errors);
+			}
+			if let Some(step) = range.step.as_ref().and_then(|x| x.value.as_ref()) {
+				visit_node(step,errors);
+			}
+		},
+		Value::For(comprehension) => {
+			for clause in comprehension.clauses.iter() {
+				match clause {
+					ForClause::Generator(generator) => if let Some(source) = &generator.source {
+						visit_node(source,errors);
+					},
+					ForClause::Filter(filter) => visit_node(filter,errors),
+				}
+			}
+			visit_paren(&comprehension.body,errors);
+		},
+	}
+}
+
+fn visit_paren<'a>(paren:&ParenExpr<'a,'_>,errors:&mut Vec<UserSideError<'a>>) {
+	if let Some(body) = &paren.body {
+		visit_node(body,errors);
+	}
+}
+
+fn visit_list<'a>(list:&ListExpr<'a,'_>,errors:&mut Vec<UserSideError<'a>>) {
+	list.items.iter().for_each(|x| visit_node(&x.value,errors));
+}
+
+fn visit_block<'a>(block:&Block<'a,'_>,errors:&mut Vec<UserSideError<'a>>) {
+	visit_paren(&block.start,errors);
+	visit_paren(&block.body,errors);
+}
+
+fn visit_lamda<'a>(lamda:&Lamda<'a,'_>,errors:&mut Vec<UserSideError<'a>>) {
+	visit_paren(&lamda.body,errors);
+}
+
+#[cfg(test)]
+use crate::{lex_full_text,token::TokenSlice,parse::parse};
+#[cfg(test)]
+use nom::InputLength;
+
+#[test]
+#[no_mangle]
+fn test_collect_errors() {
+	let text = "
+def broken(a) {
+	x = ;
+	y = a + ;
+	z = ? 1;
+	[1,, 2]
+}
+def fine(b) { b + 1 }
+";
+	let lexed = lex_full_text(text);
+	let (remaining,node) = parse(TokenSlice::new(&lexed)).unwrap();
+	assert!(remaining.input_len()==0);
+
+	let functions = match &node.base {
+		GrammerNodeBase::Sequence(nodes) => nodes,
+		_ => unreachable!("expected a sequence"),
+	};
+	assert!(functions.len()==2);
+
+	let errors = collect_errors(&functions[0]);
+	assert_eq!(errors.len(),5,"{:?}",errors);
+	assert!(matches!(&errors[0],UserSideError::MissingRightOperand(span) if span.location_line()==3));
+	assert!(matches!(&errors[1],UserSideError::MissingRightOperand(span) if span.location_line()==4));
+	assert!(matches!(&errors[2],UserSideError::UnokwenToken(span) if *span.fragment()=="?"));
+	assert!(matches!(&errors[3],UserSideError::UnexpectedTokens(spans) if *spans[0].fragment()=="1"));
+	assert!(matches!(&errors[4],UserSideError::DuplicateComma(_)));
+
+	assert!(collect_errors(&functions[1]).is_empty());
+	assert!(matches!(&functions[1].base,GrammerNodeBase::Function(def) if def.name.is_some_and(|x| *x.fragment()=="fine")));
+
+	//nothing at all is still fine
+	let (_,node) = parse(TokenSlice::new(&[])).unwrap();
+	assert!(collect_errors(&node).is_empty());
+}
@@ -6,8 +6,14 @@ use crate::token::{LexToken};
 pub enum UserSideError<'a> {
 	OverflowError(LocatedSpan<&'a str>),
 	IntOverflowError(LocatedSpan<&'a str>,u64),
+	InvalidDigit(LocatedSpan<&'a str>,u32),//the first bad digit (empty if there were none) and the radix
 	UnokwenToken(LocatedSpan<&'a str>),
 	UnclosedString(LocatedSpan<&'a str>,char),
+	UnclosedTripleString(LocatedSpan<&'a str>,LocatedSpan<&'a str>,char),//the opening quotes, the end of the file
+	UnclosedRawString(LocatedSpan<&'a str>,LocatedSpan<&'a str>,usize),//the r#", the end of the file, how many # guards
+	InvalidEscape(LocatedSpan<&'a str>),//the whole escape sequence starting at the \
+	UnclosedInterpolation(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//the #{, the last thing in the hole
+	EmptyInterpolation(LocatedSpan<&'a str>),//the #{
 
 	Compound(Vec<UserSideError<'a>>),
 
@@ -15,11 +21,45 @@ pub enum UserSideError<'a> {
 	EmptyFuncDef(LocatedSpan<&'a str>),
 	UnexpectedNameTok(LexToken<'a>),
 	ReservedName(LocatedSpan<&'a str>),
+	SeparatedClause(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//this clause's name, where the function was first defined
+	ClauseArity(LocatedSpan<&'a str>,LocatedSpan<&'a str>,usize,usize),//this clause's name, the first clause's name, expected, found
 
 	UnexpectedTokens(Vec<LocatedSpan<&'a str>>),
+
+	MissingLeftOperand(LocatedSpan<&'a str>),//the operator
+	MissingRightOperand(LocatedSpan<&'a str>),//the operator
+	PipeIntoNonCall(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//the |> and the start of what it pipes into
+	MissingPathSegment(LocatedSpan<&'a str>),//the ::
+	InvalidPathRoot(LocatedSpan<&'a str>),//the ::
+	MissingField(LocatedSpan<&'a str>),//the .
+	MissingRangeStep(LocatedSpan<&'a str>),//the :
+	ChainedRange(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//the first .. and the second
+	DanglingElse(LocatedSpan<&'a str>),
+	MissingArmArrow(LocatedSpan<&'a str>),//the end of the arm
+	MissingArmBody(LocatedSpan<&'a str>),//the =>
+	MissingMatchValue(LocatedSpan<&'a str>),//the match
+	MissingGuardCondition(LocatedSpan<&'a str>),//the if
+	UnparenthesizedGuard(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//first and last token of a def guard written without ()
+	InvalidPattern(LocatedSpan<&'a str>),
+	MissingGeneratorSource(LocatedSpan<&'a str>),//the <-
+	StrayLeftArrow(LocatedSpan<&'a str>),//a <- that is not in a for head. most likely x<-1
+	MapPercentGap(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//the % and the { that came after some space
+	NoGenerator(LocatedSpan<&'a str>),//the for
+	TrailingComma(LocatedSpan<&'a str>),
+	DuplicateComma(LocatedSpan<&'a str>),//the comma with nothing before it
+	EmptyGroup(LocatedSpan<&'a str>),//the (
+	ParenTuple(LocatedSpan<&'a str>),//the ( of (a, b)
+
+	EmptyImport(LocatedSpan<&'a str>),
+	MissingImportSegment(LocatedSpan<&'a str>),//the token before the missing name
+	MissingImportAlias(LocatedSpan<&'a str>),//the as
+	InvalidImportItem(LocatedSpan<&'a str>),
+	AliasedImportItems(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//the as, the {
 	
 	UnclosedPar(LocatedSpan<&'a str>,LocatedSpan<&'a str>),//start found
 	ExtraPar(LocatedSpan<&'a str>),
+	NestedTooDeep(LocatedSpan<&'a str>),//the opener past the limit
+	ExpectedParen(LocatedSpan<&'a str>,char),//last thing we saw, the opener we wanted
 
 
 }
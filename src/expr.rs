@@ -0,0 +1,1361 @@
+use crate::token::{TokenSlice,LexToken,LexTag,BinaryOp};
+use crate::ast::{GrammerNode,GrammerNodeBase,Value,Varible,BinaryOpNode,UnaryOpNode,FunctionCall,Func,KeyWord,SmallLexToken,Lamda,If,Else,ParenExpr,Cond,CondArm,MapEntry,Match,MatchArm,Guard,Pattern,Comprehension,ForClause,Generator,ListExpr,ListItem,MapExpr,Path,Member,RangeExpr,RangeStep};
+use crate::errors::{UserSideError,combine_errors};
+use crate::tree::{token_trees,TokenTree};
+use crate::parse::{peek,match_keyword,take_paren,process_paren,skip_to_ender,skip_till,parse_block,is_delimiter,parse_assumed_paren,parse_body,parse_params,is_op,paren_inner,unexpected_tokens};
+use crate::pattern::{parse_pattern,parse_full_pattern};
+use nom_locate::LocatedSpan;
+
+use nom::{InputLength,InputTake,InputIter};
+
+/*
+precedence table, from loosest to tightest binding.
+left associative operators bind (n,n+1) and right associative ones (n+1,n)
+
+ ||             left
+ ^^             left
+ &&             left
+ == !=          left
+ < <= > >=      left
+ |>             left  (above the comparisons like in elixir so x |> f() == y works)
+ ..             left  with |> so 0..n |> f() pipes the range. a..b:step takes the step too
+ + -            left
+ * / %          left
+ - + (prefix)
+ ** ^           right
+ . ::           left  (a::b is a path and a.b a member access, both need a name on the right)
+ f(...)         postfix call, binds like . so a::b(x) calls a::b
+
+= => -> : and | are not expression operators. they end the expression and are left to whoever called us.
+
+braces:
+ (a)            grouping, () and (a, b) are errors
+ {a, b}         tuple, {} and {a} are fine too
+ [a, b]         array
+ %{k => v}      map
+a trailing comma or two commas in a row are errors in all of them (and in call arguments).
+*/
+
+const PREFIX_BP: u8 = 19;
+const COMPARE_BP: u8 = 10;
+const CALL_BP: u8 = 22;
+
+fn infix_binding_power(op:&BinaryOp) -> Option<(u8,u8)> {
+	let ans = match op {
+		BinaryOp::Or => (2,3),
+		BinaryOp::Xor => (4,5),
+		BinaryOp::And => (6,7),
+
+		BinaryOp::TwoEqul | BinaryOp::NotEqual => (8,9),
+		BinaryOp::Smaller | BinaryOp::SmallerEqual
+		| BinaryOp::Bigger | BinaryOp::BiggerEqual => (COMPARE_BP,COMPARE_BP+1),
+
+		BinaryOp::Pipe => (12,13),
+		BinaryOp::Range => (12,13),
+
+		BinaryOp::Add | BinaryOp::Sub => (14,15),
+		BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => (16,17),
+
+		BinaryOp::Exp => (21,20),
+
+		BinaryOp::Dot | BinaryOp::DoubleDots => (22,23),
+
+		BinaryOp::OneEqul | BinaryOp::FatArrow | BinaryOp::SmallArrow | BinaryOp::LeftArrow
+		| BinaryOp::Dots | BinaryOp::SingleOr => return None,
+	};
+	Some(ans)
+}
+
+fn is_prefix(op:&BinaryOp) -> bool {
+	matches!(op,BinaryOp::Add | BinaryOp::Sub)
+}
+
+//parses a full expression from the start of the input.
+//returns None without consuming anything if the input does not start with an expression
+pub fn parse_expr<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,Option<GrammerNode<'a,'b>>) {
+	parse_expr_bp(input,0)
+}
+
+fn parse_expr_bp<'a,'b>(input:TokenSlice<'a,'b>,min_bp:u8) -> (TokenSlice<'a,'b>,Option<GrammerNode<'a,'b>>) {
+	let (mut input,lhs) = parse_prefix(input);
+	let mut lhs = match lhs {
+		Some(node) => node,
+		None => return (input,None),
+	};
+
+	while let Some(token) = peek(&input) {
+		match &token.tag {
+			LexTag::Delimiter('(') if CALL_BP >= min_bp => {
+				let (remaining,call) = parse_call(input,lhs);
+				input = remaining;
+				lhs = call;
+			}
+			//generators are split off before their parts get here so this is x<-1 lexed as x <- 1.
+			//it is taken like a comparison so the rest of the expression is not lost
+			LexTag::Op(BinaryOp::LeftArrow) if COMPARE_BP >= min_bp => {
+				let (remaining,rhs) = parse_expr_bp(input.take_split(1).0,COMPARE_BP+1);
+				let mut node = binary_node(Some(lhs),token.clone().into(),rhs);
+				node.error = combine_errors(node.error,Some(Box::new(UserSideError::StrayLeftArrow(token.span))));
+				lhs = node;
+				input = remaining;
+			}
+			LexTag::Op(op) => {
+				let (l_bp,r_bp) = match infix_binding_power(op) {
+					Some(bp) => bp,
+					None => break,
+				};
+				if l_bp < min_bp {
+					break;
+				}
+
+				if let BinaryOp::Dot | BinaryOp::DoubleDots = op {
+					let (remaining,node) = access_node(lhs,input);
+					input = remaining;
+					lhs = node;
+					continue;
+				}
+				if let BinaryOp::Range = op {
+					let (remaining,node) = range_node(lhs,input,r_bp);
+					input = remaining;
+					lhs = node;
+					continue;
+				}
+
+				let (remaining,rhs) = parse_expr_bp(input.take_split(1).0,r_bp);
+				lhs = match (op,rhs) {
+					(BinaryOp::Pipe,Some(rhs)) => pipe_node(lhs,rhs,&input),
+					(_,rhs) => binary_node(Some(lhs),token.clone().into(),rhs),
+				};
+				input = remaining;
+			}
+			_ => break,
+		}
+	}
+
+	(input,Some(lhs))
+}
+
+//everything that can start an expression
+fn parse_prefix<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,Option<GrammerNode<'a,'b>>) {
+	let token = match peek(&input) {
+		Some(token) => token,
+		None => return (input,None),
+	};
+
+	match &token.tag {
+		LexTag::Int(_) | LexTag::Float(_) | LexTag::Atom()
+		| LexTag::String(..) | LexTag::RawString(..) | LexTag::PoisonString(_) => {
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Basic(token.clone().into())).into();
+			node.error = token.error.clone();
+			(input.take_split(1).0,Some(node))
+		}
+
+		//the lexer already reported these. keeping them as a node means the expression around them is not lost
+		LexTag::Unknowen() => {
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Unprocessed(input.take_split(1).1).into();
+			node.error = token.error.clone();
+			(input.take_split(1).0,Some(node))
+		}
+
+		LexTag::Word() => match match_keyword(token.span) {
+			None => {
+				let var = Varible{name:token.span,count:0};
+				(input.take_split(1).0,Some(GrammerNodeBase::Val(Value::Var(var)).into()))
+			}
+			Some(KeyWord::Nil(span)) => {
+				(input.take_split(1).0,Some(GrammerNodeBase::Val(Value::Nil(KeyWord::Nil(span))).into()))
+			}
+			Some(keyword @ KeyWord::Lamda(_)) => {
+				let (input,params,params_error) = parse_params(input.take_split(1).0,token.span);
+				let last_span = params.end.or(params.start).unwrap_or(token.span);
+				let (input,body,body_error) = parse_assumed_paren(input,'{',last_span);
+				let body = process_paren(body,parse_body);
+				let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Func(Lamda{keyword,params: Box::new(params),body: Box::new(body)})).into();
+				node.error = combine_errors(params_error,body_error);
+				(input,Some(node))
+			}
+			Some(keyword @ KeyWord::Cond(_)) => {
+				let (input,node) = parse_cond(input.take_split(1).0,keyword);
+				(input,Some(node))
+			}
+			Some(keyword @ KeyWord::Match(_)) => {
+				let (input,node) = parse_match(input.take_split(1).0,keyword);
+				(input,Some(node))
+			}
+			Some(keyword @ KeyWord::For(_)) => {
+				let (input,node) = parse_for(input.take_split(1).0,keyword);
+				(input,Some(node))
+			}
+			Some(keyword @ KeyWord::If(_)) => {
+				let (input,node) = parse_if(input.take_split(1).0,keyword);
+				(input,Some(node))
+			}
+			//an else that does not follow an if. we still parse its body so it does not turn into junk
+			Some(keyword @ KeyWord::Else(_)) => {
+				let (input,else_block,error) = parse_else(input.take_split(1).0,keyword);
+				let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Paren(else_block.body)).into();
+				node.error = combine_errors(error,Some(Box::new(UserSideError::DanglingElse(token.span))));
+				(input,Some(node))
+			}
+			Some(_) => (input,None),
+		},
+
+		//() only groups. (a, b) is parsed as a tuple so the items are not lost but it is an error
+		LexTag::Delimiter('(') => {
+			let (input,paren,error) = take_paren(input);
+			let start = token.span;
+			let inner = paren_inner(paren.body);
+			let (after,_) = skip_to_ender(inner.clone(),',');
+
+			let (value,group_error) = if inner.input_len() == 0 {
+				(Value::Paren(ParenExpr{body: None,..paren}),Some(Box::new(UserSideError::EmptyGroup(start))))
+			} else if after.input_len() > 0 {
+				let (items,items_error) = parse_list_items(inner);
+				let list = ListExpr{start: paren.start,items,end: paren.end};
+				(Value::Tuple(list),combine_errors(Some(Box::new(UserSideError::ParenTuple(start))),items_error))
+			} else {
+				(Value::Paren(ParenExpr{body: Some(Box::new(parse_group(inner))),..paren}),None)
+			};
+
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(value).into();
+			node.error = combine_errors(error,group_error);
+			(input,Some(node))
+		}
+
+		//{a, b} is a tuple. blocks only ever come after a keyword so this is free in expression position
+		LexTag::Delimiter('{') => {
+			let (input,paren,error) = take_paren(input);
+			let (list,list_error) = parse_list(paren);
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Tuple(list)).into();
+			node.error = combine_errors(error,list_error);
+			(input,Some(node))
+		}
+
+		LexTag::Delimiter('[') => {
+			let (input,paren,error) = take_paren(input);
+			let (list,list_error) = parse_list(paren);
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Array(list)).into();
+			node.error = combine_errors(error,list_error);
+			(input,Some(node))
+		}
+
+		LexTag::StringStart(_) => {
+			let (input,node) = parse_interpolation(input);
+			(input,Some(node))
+		}
+
+		//%{ is a map. %name is lexed as an atom so there is nothing to confuse it with
+		LexTag::Op(BinaryOp::Mod) if input.input_len() > 1 && is_delimiter(&input[1],'{') => {
+			let (input,node) = parse_map(input);
+			(input,Some(node))
+		}
+
+		LexTag::Op(op) if is_prefix(op) => {
+			let (input,operand) = parse_expr_bp(input.take_split(1).0,PREFIX_BP);
+			let error = match operand {
+				None => Some(Box::new(UserSideError::MissingRightOperand(token.span))),
+				Some(_) => None,
+			};
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Unary(UnaryOpNode{
+				operator: token.clone().into(),
+				operand: operand.map(Box::new),
+			})).into();
+			node.error = error;
+			(input,Some(node))
+		}
+
+		//an operator with nothing on its left. we still parse the right side so the rest of the expression is kept
+		LexTag::Op(op) => match infix_binding_power(op) {
+			Some((_,r_bp)) => {
+				let (input,rhs) = parse_expr_bp(input.take_split(1).0,r_bp);
+				let node = binary_node(None,token.clone().into(),rhs)
+					.with_error(UserSideError::MissingLeftOperand(token.span));
+				(input,Some(node))
+			}
+			None => (input,None),
+		}
+
+		_ => (input,None),
+	}
+}
+
+//assumes the if keyword was already consumed
+fn parse_if<'a,'b>(input:TokenSlice<'a,'b>,keyword:KeyWord<'a>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let (mut input,body,mut error) = parse_block(input,keyword.get_span(),parse_group);
+
+	let mut else_block = None;
+	if let Some(token) = peek(&input) {
+		if let Some(else_word @ KeyWord::Else(_)) = match_keyword(token.span).filter(|_| token.tag == LexTag::Word()) {
+			let (remaining,parsed,else_error) = parse_else(input.take_split(1).0,else_word);
+			input = remaining;
+			else_block = Some(Box::new(parsed));
+			error = combine_errors(error,else_error);
+		}
+	}
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::If(If{keyword,body,else_block})).into();
+	node.error = error;
+	(input,node)
+}
+
+//assumes the else keyword was already consumed. else if is stored as an else whose body is the next if without braces
+fn parse_else<'a,'b>(input:TokenSlice<'a,'b>,keyword:KeyWord<'a>) -> (TokenSlice<'a,'b>,Else<'a,'b>,Option<Box<UserSideError<'a>>>) {
+	if let Some(token) = peek(&input) {
+		if let Some(if_word @ KeyWord::If(_)) = match_keyword(token.span).filter(|_| token.tag == LexTag::Word()) {
+			let (input,node) = parse_if(input.take_split(1).0,if_word);
+			let body = ParenExpr{
+				start: None,
+				body: Some(Box::new(node)),
+				end: None,
+			};
+			return (input,Else{keyword,body},None);
+		}
+	}
+
+	let (input,body,error) = parse_assumed_paren(input,'{',keyword.get_span());
+	let body = process_paren(body,parse_body);
+	(input,Else{keyword,body},error)
+}
+
+//assumes the cond keyword was already consumed
+fn parse_cond<'a,'b>(input:TokenSlice<'a,'b>,keyword:KeyWord<'a>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let (input,paren,error) = parse_assumed_paren(input,'{',keyword.get_span());
+	let (arms,arms_error) = parse_arrow_arms(paren_inner(paren.body));
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Cond(Cond{
+		keyword,
+		start: paren.start,
+		arms,
+		end: paren.end,
+	})).into();
+	node.error = combine_errors(error,arms_error);
+	(input,node)
+}
+
+//assumes input starts with a StringStart. the lexer always follows it with a hole and ends it with a StringEnd
+fn parse_interpolation<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let start = input[0].span;
+	let mut input = input;
+	let mut items = Vec::new();
+	let mut error = None;
+
+	while let Some(token) = peek(&input) {
+		match &token.tag {
+			LexTag::StringStart(value) | LexTag::StringMiddle(value) | LexTag::StringEnd(value) => {
+				//empty pieces like the one in "#{x}" add nothing
+				if !value.is_empty() || token.error.is_some() {
+					let literal = SmallLexToken{span: token.span,tag: LexTag::String('"',value.clone())};
+					let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Basic(literal)).into();
+					node.error = token.error.clone();
+					items.push(ListItem{value: node,first: token.span,last: token.span,ender: None});
+				}
+				input = input.take_split(1).0;
+				if matches!(token.tag,LexTag::StringEnd(_)) {
+					break;
+				}
+			}
+			LexTag::Delimiter('{') => {
+				let open = token.span;
+				let (remaining,paren,paren_error) = take_paren(input);
+				let close = paren.end.unwrap_or(open);
+				error = combine_errors(error,paren_error);
+				input = remaining;
+
+				let inner = paren_inner(paren.body);
+				if inner.input_len() == 0 {
+					error = combine_errors(error,Some(Box::new(UserSideError::EmptyInterpolation(open))));
+					continue;
+				}
+				items.push(ListItem{value: parse_group(inner),first: open,last: close,ender: None});
+			}
+			_ => break,
+		}
+	}
+
+	let end = items.last().map(|item| item.last).unwrap_or(start);
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Call(FunctionCall{
+		piped: None,
+		func: Func::Concat(start),
+		args: ListExpr{start: Some(start),items,end: Some(end)},
+	})).into();
+	node.error = error;
+	(input,node)
+}
+
+//assumes input starts with %{
+fn parse_map<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let percent = input[0].span;
+	//% {} is still taken as a map so the entries are not lost
+	let gap_error = match input[1].span.location_offset() == percent.location_offset()+1 {
+		true => None,
+		false => Some(Box::new(UserSideError::MapPercentGap(percent,input[1].span))),
+	};
+	let (input,paren,error) = take_paren(input.take_split(1).0);
+	let (arms,entries_error) = parse_arrow_arms(paren_inner(paren.body));
+	let entries = arms.into_iter().map(|arm| MapEntry{
+		key: arm.condition,
+		arrow: arm.arrow,
+		value: arm.body,
+		ender: arm.ender,
+	}).collect();
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Map(MapExpr{
+		percent,
+		start: paren.start,
+		entries,
+		end: paren.end,
+	})).into();
+	node.error = combine_errors(combine_errors(gap_error,error),entries_error);
+	(input,node)
+}
+
+//a => b, c => d as used by cond and maps
+fn parse_arrow_arms<'a,'b>(input:TokenSlice<'a,'b>) -> (Vec<CondArm<'a,'b>>,Option<Box<UserSideError<'a>>>) {
+	let mut arms = Vec::new();
+	let mut error = None;
+	let mut input = input;
+	while input.input_len() > 0 {
+		let (remaining,arm,ender) = take_arm(input);
+		input = remaining;
+		if arm.input_len() == 0 {
+			continue;
+		}
+
+		let (rest,condition) = parse_expr(arm.clone());
+		let condition = match condition {
+			Some(condition) => condition,
+			None => {
+				//nothing usable before the => so the whole left side is junk
+				let split = rest.position(|t| is_op(t,BinaryOp::FatArrow)).unwrap_or(rest.input_len());
+				let (_,junk) = rest.take_split(split);
+				let error = match junk.input_len() {
+					0 => UserSideError::MissingLeftOperand(rest[0].span),
+					_ => unexpected_tokens(&junk),
+				};
+				GrammerNode::from(GrammerNodeBase::Unprocessed(junk)).with_error(error)
+			}
+		};
+		let rest = match condition.base {
+			GrammerNodeBase::Unprocessed(ref junk) => rest.take_split(junk.input_len()).0,
+			_ => rest,
+		};
+
+		let (arrow,body,arm_error) = parse_arm_body(&arm,rest);
+		error = combine_errors(error,arm_error);
+		arms.push(CondArm{
+			condition: Box::new(condition),
+			arrow,
+			body: body.map(Box::new),
+			ender,
+		});
+	}
+	(arms,error)
+}
+
+//assumes the match keyword was already consumed
+fn parse_match<'a,'b>(input:TokenSlice<'a,'b>,keyword:KeyWord<'a>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	//match {a, b} {...} matches on a tuple but a lone {...} is the arms with the value missing
+	let (input,value) = match peek(&input) {
+		Some(token) if is_delimiter(token,'{') => match token_trees(input.clone()).nth(1) {
+			Some(TokenTree::Group(arms)) if is_delimiter(arms.open,'{') => parse_expr(input),
+			_ => (input,None),
+		},
+		_ => parse_expr(input),
+	};
+	let mut error = match value {
+		None => Some(Box::new(UserSideError::MissingMatchValue(keyword.get_span()))),
+		Some(_) => None,
+	};
+
+	let (input,paren,paren_error) = parse_assumed_paren(input,'{',keyword.get_span());
+	error = combine_errors(error,paren_error);
+
+	let mut arms = Vec::new();
+	let mut inner = paren_inner(paren.body);
+	while inner.input_len() > 0 {
+		let (remaining,arm,ender) = take_arm(inner);
+		inner = remaining;
+		if arm.input_len() == 0 {
+			continue;
+		}
+
+		let (rest,pattern,pattern_error) = parse_pattern(arm.clone());
+		let (rest,pattern) = match pattern {
+			Some(pattern) => (rest,pattern),
+			None => {
+				//skip to whatever comes after the pattern
+				let split = rest.position(|t| is_op(t,BinaryOp::FatArrow) || is_guard(t)).unwrap_or(rest.input_len());
+				let bad = rest[0].span;
+				error = combine_errors(error,Some(Box::new(UserSideError::InvalidPattern(bad))));
+				(rest.take_split(split).0,Pattern::Invalid(bad))
+			}
+		};
+		error = combine_errors(error,pattern_error);
+
+		let (rest,guard) = match peek(&rest) {
+			Some(token) if is_guard(token) => {
+				let keyword = KeyWord::If(token.span);
+				let (rest,condition) = parse_expr(rest.take_split(1).0);
+				if condition.is_none() {
+					error = combine_errors(error,Some(Box::new(UserSideError::MissingGuardCondition(token.span))));
+				}
+				(rest,Some(Guard{keyword,condition:condition.map(Box::new)}))
+			}
+			_ => (rest,None),
+		};
+
+		let (arrow,body,arm_error) = parse_arm_body(&arm,rest);
+		error = combine_errors(error,arm_error);
+		arms.push(MatchArm{
+			pattern,
+			guard,
+			arrow,
+			body: body.map(Box::new),
+			ender,
+		});
+	}
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Match(Match{
+		keyword,
+		value: value.map(Box::new),
+		start: paren.start,
+		arms,
+		end: paren.end,
+	})).into();
+	node.error = error;
+	(input,node)
+}
+
+//assumes the for keyword was already consumed
+fn parse_for<'a,'b>(input:TokenSlice<'a,'b>,keyword:KeyWord<'a>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let (input,head,mut error) = parse_assumed_paren(input,'(',keyword.get_span());
+	let last_span = head.end.or(head.start).unwrap_or(keyword.get_span());
+
+	let (clauses,clauses_error) = parse_for_clauses(paren_inner(head.body));
+	error = combine_errors(error,clauses_error);
+	if head.start.is_some() && !clauses.iter().any(|x| matches!(x,ForClause::Generator(_))) {
+		error = combine_errors(error,Some(Box::new(UserSideError::NoGenerator(keyword.get_span()))));
+	}
+
+	let (input,body,body_error) = parse_assumed_paren(input,'{',last_span);
+	error = combine_errors(error,body_error);
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::For(Comprehension{
+		keyword,
+		start: head.start,
+		clauses,
+		end: head.end,
+		body: process_paren(body,parse_body),
+	})).into();
+	node.error = error;
+	(input,node)
+}
+
+//x <- xs, x > 1. anything with a <- at the top is a generator and the rest are filters
+fn parse_for_clauses<'a,'b>(input:TokenSlice<'a,'b>) -> (Vec<ForClause<'a,'b>>,Option<Box<UserSideError<'a>>>) {
+	let mut input = input;
+	let mut clauses = Vec::new();
+	let mut error = None;
+
+	while input.input_len() > 0 {
+		let (remaining,item) = skip_to_ender(input,',');
+		let ender = peek(&remaining).map(|token| token.span);
+		input = match remaining.input_len() {
+			0 => remaining,
+			_ => remaining.take_split(1).0,
+		};
+
+		if item.input_len() == 0 {
+			error = combine_errors(error,ender.map(|x| Box::new(UserSideError::DuplicateComma(x))));
+			continue;
+		}
+		if input.input_len() == 0 {
+			error = combine_errors(error,ender.map(|x| Box::new(UserSideError::TrailingComma(x))));
+		}
+
+		let (source,pattern) = skip_till(item.clone(),|t| is_op(t,BinaryOp::LeftArrow));
+		if source.input_len() == 0 {
+			clauses.push(ForClause::Filter(parse_group(item)));
+			continue;
+		}
+
+		let arrow = source[0].span;
+		let pattern = match pattern.input_len() {
+			0 => {
+				error = combine_errors(error,Some(Box::new(UserSideError::InvalidPattern(arrow))));
+				Pattern::Invalid(arrow)
+			}
+			_ => {
+				let (pattern,pattern_error) = parse_full_pattern(pattern);
+				error = combine_errors(error,pattern_error);
+				pattern
+			}
+		};
+
+		let source = source.take_split(1).0;
+		let source = match source.input_len() {
+			0 => {
+				error = combine_errors(error,Some(Box::new(UserSideError::MissingGeneratorSource(arrow))));
+				None
+			}
+			_ => Some(Box::new(parse_group(source))),
+		};
+		clauses.push(ForClause::Generator(Generator{pattern,arrow,source}));
+	}
+
+	(clauses,error)
+}
+
+pub fn is_guard(token:&LexToken) -> bool {
+	token.tag == LexTag::Word() && matches!(match_keyword(token.span),Some(KeyWord::If(_)))
+}
+
+//splits the next arm off a comma separated list of arms. returns the rest, the arm and its , if there was one
+fn take_arm<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,TokenSlice<'a,'b>,Option<LocatedSpan<&'a str>>) {
+	let (input,arm) = skip_to_ender(input,',');
+	match peek(&input) {
+		Some(token) => (input.take_split(1).0,arm,Some(token.span)),
+		None => (input,arm,None),
+	}
+}
+
+//the => body part of an arm. arm is the whole arm and rest is what is left after its left side
+fn parse_arm_body<'a,'b>(arm:&TokenSlice<'a,'b>,rest:TokenSlice<'a,'b>) -> (Option<LocatedSpan<&'a str>>,Option<GrammerNode<'a,'b>>,Option<Box<UserSideError<'a>>>) {
+	let arrow = match peek(&rest) {
+		Some(token) if is_op(token,BinaryOp::FatArrow) => token.span,
+		_ => {
+			let consumed = arm.input_len()-rest.input_len();
+			let last = arm[consumed.max(1)-1].span;
+			let mut error = Some(Box::new(UserSideError::MissingArmArrow(last)));
+			if rest.input_len() > 0 {
+				error = combine_errors(error,Some(Box::new(unexpected_tokens(&rest))));
+			}
+			return (None,None,error);
+		}
+	};
+
+	let (rest,body) = parse_expr(rest.take_split(1).0);
+	let mut error = match body {
+		None => Some(Box::new(UserSideError::MissingArmBody(arrow))),
+		Some(_) => None,
+	};
+	if rest.input_len() > 0 {
+		error = combine_errors(error,Some(Box::new(unexpected_tokens(&rest))));
+	}
+	(Some(arrow),body,error)
+}
+
+//left is None either for prefix operators or when the operand is missing (which carries an error)
+fn binary_node<'a,'b>(left:Option<GrammerNode<'a,'b>>,operator:SmallLexToken<'a>,right:Option<GrammerNode<'a,'b>>) -> GrammerNode<'a,'b> {
+	let error = match right {
+		None => Some(Box::new(UserSideError::MissingRightOperand(operator.span))),
+		Some(_) => None,
+	};
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Op(BinaryOpNode{
+		left: left.map(Box::new),
+		operator,
+		right: right.map(Box::new),
+	})).into();
+	node.error = error;
+	node
+}
+
+//a::b and a.b. input is still pointing at the operator
+fn access_node<'a,'b>(lhs:GrammerNode<'a,'b>,input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let operator = &input[0];
+	let input = input.take_split(1).0;
+	let (input,name) = match peek(&input) {
+		Some(token) if token.tag == LexTag::Word() && match_keyword(token.span).is_none() => (input.take_split(1).0,Some(token.span)),
+		_ => (input,None),
+	};
+
+	if is_op(operator,BinaryOp::Dot) {
+		let error = match name {
+			None => Some(Box::new(UserSideError::MissingField(operator.span))),
+			Some(_) => None,
+		};
+		let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Member(Member{
+			value: Box::new(lhs),
+			dot: operator.span,
+			field: name,
+		})).into();
+		node.error = error;
+		return (input,node);
+	}
+
+	let mut segments = match lhs.base {
+		GrammerNodeBase::Val(Value::Var(var)) => vec![var.name],
+		GrammerNodeBase::Val(Value::Path(path)) => path.segments,
+		base => {
+			//f(x)::y we keep both sides around as an op so nothing is lost
+			let right = name.map(|name| GrammerNode::from(GrammerNodeBase::Val(Value::Var(Varible{name,count:0}))));
+			let mut node = binary_node(Some(GrammerNode{base,..lhs}),operator.clone().into(),right);
+			node.error = combine_errors(node.error,Some(Box::new(UserSideError::InvalidPathRoot(operator.span))));
+			return (input,node);
+		}
+	};
+
+	let mut error = lhs.error;
+	match name {
+		Some(name) => segments.push(name),
+		None => error = combine_errors(error,Some(Box::new(UserSideError::MissingPathSegment(operator.span)))),
+	}
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Path(Path{segments})).into();
+	node.error = error;
+	(input,node)
+}
+
+//the step of a range comes after its end so it is picked up here instead of being an operator.
+//input is still pointing at the ..
+fn range_node<'a,'b>(lhs:GrammerNode<'a,'b>,input:TokenSlice<'a,'b>,r_bp:u8) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let dots = input[0].span;
+	let (input,end) = parse_expr_bp(input.take_split(1).0,r_bp);
+	let mut error = match end {
+		None => Some(Box::new(UserSideError::MissingRightOperand(dots))),
+		Some(_) => None,
+	};
+	//a..b..c has no clear meaning so it needs parens
+	if let GrammerNodeBase::Val(Value::Range(ref inner)) = lhs.base {
+		error = combine_errors(Some(Box::new(UserSideError::ChainedRange(inner.dots,dots))),error);
+	}
+
+	let (input,step) = match peek(&input) {
+		Some(token) if is_op(token,BinaryOp::Dots) => {
+			let (input,value) = parse_expr_bp(input.take_split(1).0,r_bp);
+			if value.is_none() {
+				error = combine_errors(error,Some(Box::new(UserSideError::MissingRangeStep(token.span))));
+			}
+			(input,Some(RangeStep{colon: token.span,value: value.map(Box::new)}))
+		}
+		_ => (input,None),
+	};
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Range(RangeExpr{
+		start: Box::new(lhs),
+		dots,
+		end: end.map(Box::new),
+		step,
+	})).into();
+	node.error = error;
+	(input,node)
+}
+
+//x |> f(a) becomes f(x, a) with x stored in piped. like elixir the piped value is the first argument.
+//input is still pointing at the |> so we can report where the target starts
+fn pipe_node<'a,'b>(lhs:GrammerNode<'a,'b>,rhs:GrammerNode<'a,'b>,input:&TokenSlice<'a,'b>) -> GrammerNode<'a,'b> {
+	match rhs.base {
+		GrammerNodeBase::Val(Value::Call(mut call)) if call.piped.is_none() => {
+			let (value,error) = lhs.into_value();
+			call.piped = value.map(Box::new);
+
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Call(call)).into();
+			node.error = combine_errors(error,rhs.error);
+			node
+		}
+		base => {
+			let (pipe,target) = (input[0].span,input[1].span);
+			let rhs = GrammerNode{base,..rhs};
+			binary_node(Some(lhs),input[0].clone().into(),Some(rhs))
+				.with_error(UserSideError::PipeIntoNonCall(pipe,target))
+		}
+	}
+}
+
+//assumes the input starts with (
+fn parse_call<'a,'b>(input:TokenSlice<'a,'b>,callee:GrammerNode<'a,'b>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let (input,paren,error) = take_paren(input);
+	let (args,args_error) = parse_list(paren);
+
+	let func = match callee.base {
+		GrammerNodeBase::Val(Value::Var(var)) => Func::Vared(var),
+		GrammerNodeBase::Val(Value::Path(path)) if callee.error.is_none() => Func::Pathed(path),
+		GrammerNodeBase::Val(Value::Func(lamda)) if callee.error.is_none() => Func::Anon(lamda),
+		base => Func::Computed(Box::new(GrammerNode{base,..callee})),
+	};
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Val(Value::Call(FunctionCall{
+		piped: None,
+		func,
+		args,
+	})).into();
+	node.error = combine_errors(error,args_error);
+	(input,node)
+}
+
+//the inside of a grouping paren (a + b)
+pub fn parse_group<'a,'b>(input:TokenSlice<'a,'b>) -> GrammerNode<'a,'b> {
+	let (remaining,node) = parse_expr(input.clone());
+	match node {
+		Some(mut node) => {
+			if remaining.input_len() > 0 {
+				node.error = combine_errors(node.error,Some(Box::new(unexpected_tokens(&remaining))));
+			}
+			node
+		}
+		None => GrammerNode::from(GrammerNodeBase::Unprocessed(input.clone()))
+			.with_error(unexpected_tokens(&input)),
+	}
+}
+
+//the items of a paren we already took
+pub fn parse_list<'a,'b>(paren:ParenExpr<'a,'b>) -> (ListExpr<'a,'b>,Option<Box<UserSideError<'a>>>) {
+	let (items,error) = parse_list_items(paren_inner(paren.body));
+	(ListExpr{start: paren.start,items,end: paren.end},error)
+}
+
+//a, b, c keeping where every item is. errors in an item stay on it, only comma errors are returned
+pub fn parse_list_items<'a,'b>(input:TokenSlice<'a,'b>) -> (Vec<ListItem<'a,'b>>,Option<Box<UserSideError<'a>>>) {
+	let mut input = input;
+	let mut items = Vec::new();
+	let mut error = None;
+
+	while input.input_len() > 0 {
+		let (remaining,item) = parse_expr(input.clone());
+		let (remaining,junk) = skip_to_ender(remaining,',');
+		let (_,tokens) = input.take_split(input.input_len()-remaining.input_len());
+
+		//drop the ,
+		let ender = peek(&remaining).map(|token| token.span);
+		input = match remaining.input_len() {
+			0 => remaining,
+			_ => remaining.take_split(1).0,
+		};
+
+		if tokens.input_len() == 0 {
+			error = combine_errors(error,ender.map(|x| Box::new(UserSideError::DuplicateComma(x))));
+			continue;
+		}
+		if input.input_len() == 0 {
+			error = combine_errors(error,ender.map(|x| Box::new(UserSideError::TrailingComma(x))));
+		}
+
+		let junk_error = match junk.input_len() {
+			0 => None,
+			_ => Some(Box::new(unexpected_tokens(&junk))),
+		};
+		let value = match item {
+			Some(mut item) => {
+				item.error = combine_errors(item.error,junk_error);
+				item
+			}
+			None => {
+				let error = unexpected_tokens(&junk);
+				GrammerNode::from(GrammerNodeBase::Unprocessed(junk)).with_error(error)
+			}
+		};
+
+		items.push(ListItem{
+			value,
+			first: tokens[0].span,
+			last: TokenSlice::last(&tokens).unwrap().span,
+			ender,
+		});
+	}
+
+	(items,error)
+}
+
+#[cfg(test)]
+use crate::lex_full_text;
+
+//renders an expression as an s-expression so tests can compare shapes
+#[cfg(test)]
+pub fn show(node:&GrammerNode) -> String {
+	//numbered bindings show as x#n
+	fn show_var(var:&Varible) -> String {
+		match var.count {
+			0 => var.name.fragment().to_string(),
+			count => format!("{}#{}",var.name.fragment(),count),
+		}
+	}
+
+	fn show_path(path:&Path) -> String {
+		path.segments.iter().map(|x| x.fragment().to_string()).collect::<Vec<_>>().join("::")
+	}
+
+	fn show_list(list:&ListExpr) -> String {
+		list.items.iter().map(|item| show(&item.value)).collect::<Vec<_>>().join(" ")
+	}
+
+	fn show_lamda(lamda:&Lamda) -> String {
+		let params = lamda.params.items.iter().map(crate::pattern::show_pattern).collect::<Vec<_>>().join(" ");
+		let body = lamda.body.body.as_deref().map(show).unwrap_or_default();
+		format!("fn({}){{{}}}",params,body)
+	}
+
+	fn show_value(value:&Value) -> String {
+		match value {
+			Value::Basic(token) => token.span.fragment().to_string(),
+			Value::Nil(_) => "nil".to_string(),
+			Value::Var(var) => show_var(var),
+			Value::Path(path) => show_path(path),
+			Value::Member(member) => format!("(. {} {})",show(&member.value),member.field.map(|x| x.fragment().to_string()).unwrap_or("_".to_string())),
+			Value::Paren(paren) => paren.body.as_deref().map(show).unwrap_or("()".to_string()),
+			Value::Unary(op) => format!("({} {})",
+				op.operator.span.fragment(),
+				op.operand.as_deref().map(show).unwrap_or("_".to_string()),
+			),
+			Value::Op(op) => format!("({} {} {})",
+				op.operator.span.fragment(),
+				op.left.as_deref().map(show).unwrap_or("_".to_string()),
+				op.right.as_deref().map(show).unwrap_or("_".to_string()),
+			),
+			Value::Call(call) => {
+				let func = match &call.func {
+					Func::Vared(var) => show_var(var),
+					Func::Pathed(path) => show_path(path),
+					Func::Anon(lamda) => show_lamda(lamda),
+					Func::Computed(node) => show(node),
+					Func::Concat(_) => "concat".to_string(),
+					_ => "?".to_string(),
+				};
+				let args = show_list(&call.args);
+				match &call.piped {
+					Some(piped) => format!("{}[{}|{}]",func,show_value(piped),args),
+					None => format!("{}[{}]",func,args),
+				}
+			}
+			Value::Func(lamda) => show_lamda(lamda),
+			Value::If(if_expr) => {
+				let cond = if_expr.body.start.body.as_deref().map(show).unwrap_or_default();
+				let body = if_expr.body.body.body.as_deref().map(show).unwrap_or_default();
+				let else_body = match &if_expr.else_block {
+					Some(else_block) => match else_block.body.start {
+						Some(_) => format!("{{{}}}",else_block.body.body.as_deref().map(show).unwrap_or_default()),
+						None => else_block.body.body.as_deref().map(show).unwrap_or_default(),
+					},
+					None => "nil".to_string(),
+				};
+				format!("if({}){{{}}}else {}",cond,body,else_body)
+			}
+			Value::Cond(cond) => {
+				let arms = cond.arms.iter().map(|arm| format!("{} => {}",
+					show(&arm.condition),
+					arm.body.as_deref().map(show).unwrap_or("_".to_string()),
+				)).collect::<Vec<_>>();
+				format!("cond{{{}}}",arms.join(", "))
+			}
+			Value::Match(match_expr) => {
+				let arms = match_expr.arms.iter().map(|arm| format!("{}{} => {}",
+					crate::pattern::show_pattern(&arm.pattern),
+					arm.guard.as_ref().map(|g| format!(" if {}",g.condition.as_deref().map(show).unwrap_or_default())).unwrap_or_default(),
+					arm.body.as_deref().map(show).unwrap_or("_".to_string()),
+				)).collect::<Vec<_>>();
+				let value = match_expr.value.as_deref().map(show).unwrap_or_default();
+				format!("match {}{{{}}}",value,arms.join(", "))
+			}
+			Value::Map(map) => {
+				let entries = map.entries.iter().map(|entry| format!("{} => {}",
+					show(&entry.key),
+					entry.value.as_deref().map(show).unwrap_or("_".to_string()),
+				)).collect::<Vec<_>>();
+				format!("%{{{}}}",entries.join(", "))
+			}
+			Value::Array(list) => format!("[{}]",show_list(list)),
+			Value::Tuple(list) => format!("{{{}}}",show_list(list)),
+			Value::Range(range) => format!("(.. {} {}{})",
+				show(&range.start),
+				range.end.as_deref().map(show).unwrap_or("_".to_string()),
+				range.step.as_ref().map(|step| format!(" {}",step.value.as_deref().map(show).unwrap_or("_".to_string()))).unwrap_or_default(),
+			),
+			Value::For(comprehension) => {
+				let clauses = comprehension.clauses.iter().map(|clause| match clause {
+					ForClause::Generator(generator) => format!("{} <- {}",
+						crate::pattern::show_pattern(&generator.pattern),
+						generator.source.as_deref().map(show).unwrap_or("_".to_string()),
+					),
+					ForClause::Filter(filter) => show(filter),
+				}).collect::<Vec<_>>();
+				format!("for({}){{{}}}",clauses.join(", "),comprehension.body.body.as_deref().map(show).unwrap_or_default())
+			}
+		}
+	}
+
+	match &node.base {
+		GrammerNodeBase::Val(value) => show_value(value),
+		GrammerNodeBase::Return(ret) => ret.value.as_ref().map(show_value).unwrap_or_default(),
+		GrammerNodeBase::Sequence(nodes) => nodes.iter().map(show).collect::<Vec<_>>().join(" "),
+		GrammerNodeBase::Declare(assign) => format!("{}={}",show_var(&assign.left),assign.right.as_deref().map(show_value).unwrap_or("_".to_string())),
+		GrammerNodeBase::Function(def) => def.clauses.iter().map(|clause| format!("def({}){}{{{}}}",
+			clause.params.items.iter().map(crate::pattern::show_pattern).collect::<Vec<_>>().join(" "),
+			clause.guard.as_ref().and_then(|x| x.condition.as_deref()).map(|x| format!(" if({})",show(x))).unwrap_or_default(),
+			clause.body.body.as_deref().map(show).unwrap_or_default(),
+		)).collect::<Vec<_>>().join(" "),
+		_ => "?".to_string(),
+	}
+}
+
+#[cfg(test)]
+fn parse_str(input:&str) -> String {
+	let lexed = lex_full_text(input);
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0,"leftover tokens in {:?}",input);
+	let node = node.unwrap();
+	assert!(node.error.is_none(),"unexpected error in {:?}",input);
+	show(&node)
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_precedence() {
+	assert_eq!(parse_str("a + b * c"),"(+ a (* b c))");
+	assert_eq!(parse_str("a - b - c"),"(- (- a b) c)");
+	assert_eq!(parse_str("a ** b ^ c"),"(** a (^ b c))");
+	assert_eq!(parse_str("-a ** 2"),"(- (** a 2))");
+	assert_eq!(parse_str("-a * 2"),"(* (- a) 2)");
+	assert_eq!(parse_str("a < b + 1 && c == d || e ^^ f"),"(|| (&& (< a (+ b 1)) (== c d)) (^^ e f))");
+	assert_eq!(parse_str("x |> f() == y"),"(== f[x|] y)");
+	assert_eq!(parse_str("(a + b) % c"),"(% (+ a b) c)");
+	assert_eq!(parse_str("a::b(x, 1).c"),"(. a::b[x 1] c)");
+	assert_eq!(parse_str("f(1)(2)"),"f[1][2]");
+	assert_eq!(parse_str("%ok != nil"),"(!= %ok nil)");
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_missing_operands() {
+	let lexed = lex_full_text("a + ");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	assert_eq!(show(&node),"(+ a _)");
+	assert!(matches!(node.error.as_deref(),Some(UserSideError::MissingRightOperand(_))));
+
+	let lexed = lex_full_text("* b + c");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	let node = node.unwrap();
+	assert_eq!(show(&node),"(+ (* _ b) c)");
+	//a real prefix op has its own node so it never looks like this
+	let lexed = lex_full_text("-b");
+	let (_,prefix) = parse_expr(TokenSlice::new(&lexed));
+	assert!(matches!(prefix.unwrap().base,GrammerNodeBase::Val(Value::Unary(_))));
+	match &node.base {
+		GrammerNodeBase::Val(Value::Op(op)) => {
+			let left = op.left.as_deref().unwrap();
+			assert!(matches!(left.error.as_deref(),Some(UserSideError::MissingLeftOperand(_))));
+		}
+		_ => unreachable!(),
+	}
+
+	//stops at things that are not expressions
+	let lexed = lex_full_text("a + b = c");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert_eq!(show(&node.unwrap()),"(+ a b)");
+	assert!(remaining.input_len()==2);
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_pipe() {
+	assert_eq!(parse_str("x |> f(a) |> g()"),"g[f[x|a]|]");
+	assert_eq!(parse_str("a + b |> f(c, d)"),"f[(+ a b)|c d]");
+	assert_eq!(parse_str("x |> m::f(1)"),"m::f[x|1]");
+	assert_eq!(parse_str("x |> f(1)(2)"),"f[1][x|2]");
+
+	let lexed = lex_full_text("x |> 3");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	assert_eq!(show(&node),"(|> x 3)");
+	match node.error.as_deref() {
+		Some(UserSideError::PipeIntoNonCall(pipe,target)) => {
+			assert_eq!(*pipe.fragment(),"|>");
+			assert_eq!(*target.fragment(),"3");
+		}
+		_ => unreachable!("expected a pipe error"),
+	}
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_lamda() {
+	assert_eq!(parse_str("fn(x, y) { x + y }"),"fn($x $y){(+ x y)}");
+	assert_eq!(parse_str("map(xs, lamda(x) { x * 2 })"),"map[xs fn($x){(* x 2)}]");
+	assert_eq!(parse_str("fn(x) { x }(3)"),"fn($x){x}[3]");
+	assert_eq!(parse_str("xs |> map(fn(x) { f(x); x })"),"map[xs|fn($x){f[x] x}]");
+	//the head is made of patterns like a def
+	assert_eq!(parse_str("fn({a, _}, 0) { a }"),"fn({$a _} 0){a}");
+
+	let lexed = lex_full_text("fn(a + 1) { a }");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(matches!(node.unwrap().error.as_deref(),Some(UserSideError::UnexpectedTokens(..))));
+
+	//the block logic is shared with def so errors look the same
+	let lexed = lex_full_text("fn { x }");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	let node = node.unwrap();
+	assert_eq!(show(&node),"fn(){x}");
+	assert!(matches!(node.error.as_deref(),Some(UserSideError::ExpectedParen(_,'('))));
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_if() {
+	assert_eq!(parse_str("if (a > b) { a } else { b }"),"if((> a b)){a}else {b}");
+	assert_eq!(parse_str("if (a) { 1 }"),"if(a){1}else nil");
+	assert_eq!(parse_str("if (a) { 1 } else if (b) { 2 } else { 3 }"),"if(a){1}else if(b){2}else {3}");
+	assert_eq!(parse_str("f(if (a) { 1 } else { 2 }) + 1"),"(+ f[if(a){1}else {2}] 1)");
+
+	let lexed = lex_full_text("else { 2 }");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	match node.unwrap().error.as_deref() {
+		Some(UserSideError::DanglingElse(span)) => assert_eq!(*span.fragment(),"else"),
+		_ => unreachable!("expected a dangling else"),
+	}
+
+	//a statement break between the if and the else leaves the else dangling
+	let lexed = lex_full_text("if (a) { 1 }; else { 2 }");
+	let body = parse_body(TokenSlice::new(&lexed));
+	match body.base {
+		GrammerNodeBase::Sequence(nodes) => {
+			assert!(nodes[0].error.is_none());
+			assert!(matches!(nodes[1].error.as_deref(),Some(UserSideError::DanglingElse(_))));
+		}
+		_ => unreachable!("expected a sequence"),
+	}
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_cond() {
+	assert_eq!(parse_str("cond { a > 1 => x, b => y, _ => z }"),"cond{(> a 1) => x, b => y, _ => z}");
+	assert_eq!(parse_str("cond { f(a, b) => g(1, 2), }"),"cond{f[a b] => g[1 2]}");
+	assert_eq!(parse_str("1 + cond { a => 1 }"),"(+ 1 cond{a => 1})");
+
+	let lexed = lex_full_text("cond { a > 1 x, b =>, => 3 }");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	let node = node.unwrap();
+	let errors = match node.error.as_deref() {
+		Some(UserSideError::Compound(errors)) => errors.clone(),
+		_ => unreachable!("expected several errors"),
+	};
+	assert!(matches!(&errors[0],UserSideError::MissingArmArrow(span) if *span.fragment()=="1"));
+	assert!(matches!(&errors[1],UserSideError::UnexpectedTokens(_)));
+	assert!(matches!(&errors[2],UserSideError::MissingArmBody(span) if *span.fragment()=="=>"));
+
+	match &node.base {
+		GrammerNodeBase::Val(Value::Cond(cond)) => {
+			assert!(cond.arms.len()==3);
+			assert!(cond.arms[0].arrow.is_none());
+			assert!(cond.arms[1].body.is_none());
+			assert!(cond.arms[1].ender.is_some());
+			assert!(matches!(cond.arms[2].condition.error.as_deref(),Some(UserSideError::MissingLeftOperand(_))));
+			assert!(cond.start.is_some() && cond.end.is_some());
+		}
+		_ => unreachable!("expected a cond"),
+	}
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_match() {
+	assert_eq!(
+		parse_str("match f(x) { {%ok, [a, _]} => a, {%error, e} if e != nil => g(e), :other => 1, _ => nil }"),
+		"match f[x]{{%ok [$a _]} => a, {%error $e} if (!= e nil) => g[e], :other => 1, _ => nil}"
+	);
+	assert_eq!(parse_str("match n { 0 => 1, n if (n > 0) => n * 2 }"),"match n{0 => 1, $n if (> n 0) => (* n 2)}");
+	assert_eq!(parse_str("match {a, b} { {x, _} => x }"),"match {a b}{{$x _} => x}");
+	assert_eq!(parse_str("match x { -1 => 0, _ => 1 }"),"match x{-1 => 0, _ => 1}");
+
+	let lexed = lex_full_text("match { a + 1 => x, y if => z }");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	let node = node.unwrap();
+	let errors = match node.error.as_deref() {
+		Some(UserSideError::Compound(errors)) => errors.clone(),
+		_ => unreachable!("expected several errors"),
+	};
+	assert!(errors.iter().any(|e| matches!(e,UserSideError::MissingMatchValue(_))));
+	assert!(errors.iter().any(|e| matches!(e,UserSideError::MissingArmArrow(span) if *span.fragment()=="a")));
+	assert!(errors.iter().any(|e| matches!(e,UserSideError::MissingGuardCondition(_))));
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_range() {
+	assert_eq!(parse_str("1..10"),"(.. 1 10)");
+	assert_eq!(parse_str("0..n-1:2"),"(.. 0 (- n 1) 2)");
+	assert_eq!(parse_str("a..b:step"),"(.. a b step)");
+	assert_eq!(parse_str("a..b:c+1"),"(.. a b (+ c 1))");
+	assert_eq!(parse_str("a..b:2+1"),"(.. a b (+ 2 1))");
+	assert_eq!(parse_str("0..n:step*2"),"(.. 0 n (* step 2))");
+	assert_eq!(parse_str("(a..b)..c"),"(.. (.. a b) c)");
+	assert_eq!(parse_str("x |> f()..y < z"),"(< (.. f[x|] y) z)");
+	assert_eq!(parse_str("0..n |> g()"),"g[(.. 0 n)|]");
+	assert_eq!(parse_str("for (i <- 0..len(xs)) { xs(i) }"),"for($i <- (.. 0 len[xs])){xs[i]}");
+
+	let lexed = lex_full_text("1..");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(matches!(node.unwrap().error.as_deref(),Some(UserSideError::MissingRightOperand(span)) if *span.fragment()==".."));
+
+	let lexed = lex_full_text("1..5:");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(matches!(node.unwrap().error.as_deref(),Some(UserSideError::MissingRangeStep(span)) if *span.fragment()==":"));
+
+	let lexed = lex_full_text("a..b..c");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	assert_eq!(show(&node),"(.. (.. a b) c)");
+	assert!(matches!(node.error.as_deref(),Some(UserSideError::ChainedRange(first,second)) if first.location_offset()==1 && second.location_offset()==4));
+
+	let lexed = lex_full_text("(a..b)..c");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(node.unwrap().error.is_none());
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_interpolation() {
+	assert_eq!(parse_str("\"hi #{name}!\""),"concat[\"hi  name !\"]");
+	assert_eq!(parse_str("\"#{a + 1}#{f(b)}\""),"concat[(+ a 1) f[b]]");
+
+	let lexed = lex_full_text("\"a #{} b\"");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(matches!(node.unwrap().error.as_deref(),Some(UserSideError::EmptyInterpolation(span)) if *span.fragment()=="#{"));
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_for() {
+	assert_eq!(
+		parse_str("for (x <- xs, y <- ys, x < y) { x * y }"),
+		"for($x <- xs, $y <- ys, (< x y)){(* x y)}"
+	);
+	assert_eq!(parse_str("for ({%ok, v} <- f(a), v != nil) { v }"),"for({%ok $v} <- f[a], (!= v nil)){v}");
+
+	let lexed = lex_full_text("for (1 + 1 <-, x > 0,) { x }");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	let errors = match node.unwrap().error.as_deref() {
+		Some(UserSideError::Compound(errors)) => errors.clone(),
+		_ => unreachable!("expected several errors"),
+	};
+	assert!(errors.iter().any(|e| matches!(e,UserSideError::UnexpectedTokens(_))));
+	assert!(errors.iter().any(|e| matches!(e,UserSideError::MissingGeneratorSource(span) if *span.fragment()=="<-")));
+	assert!(errors.iter().any(|e| matches!(e,UserSideError::TrailingComma(_))));
+
+	let lexed = lex_full_text("for (x > 0) { x }");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(matches!(node.unwrap().error.as_deref(),Some(UserSideError::NoGenerator(span)) if *span.fragment()=="for"));
+
+	//outside a for head <- is almost always a comparison with a negative number
+	let lexed = lex_full_text("x<-1 && y");
+	let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+	assert!(remaining.input_len()==0);
+	assert_eq!(show(&node.unwrap()),"(&& (<- x 1) y)");
+	let lexed = lex_full_text("x<-1");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	assert!(matches!(node.error.as_deref(),Some(UserSideError::StrayLeftArrow(span)) if *span.fragment()=="<-"));
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_array() {
+	assert_eq!(parse_str("[a, b + 1, [c]]"),"[a (+ b 1) [c]]");
+	assert_eq!(parse_str("[]"),"[]");
+	assert_eq!(parse_str("[1, 2](0) + 1"),"(+ [1 2][0] 1)");
+
+	let lexed = lex_full_text("[f(x, y), b c]");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	let items = match &node.base {
+		GrammerNodeBase::Val(Value::Array(array)) => &array.items,
+		_ => unreachable!("expected an array"),
+	};
+	assert_eq!((*items[0].first.fragment(),*items[0].last.fragment()),("f",")"));
+	assert_eq!(items[0].ender.map(|x| *x.fragment()),Some(","));
+	assert_eq!((*items[1].first.fragment(),*items[1].last.fragment()),("b","c"));
+	assert!(matches!(items[1].value.error.as_deref(),Some(UserSideError::UnexpectedTokens(_))));
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_map() {
+	assert_eq!(parse_str("%{ %a => 1, \"b\" => x + 2 }"),"%{%a => 1, \"b\" => (+ x 2)}");
+	assert_eq!(parse_str("%{}"),"%{}");
+	assert_eq!(parse_str("%{1 => 2}(k) == %not_found"),"(== %{1 => 2}[k] %not_found)");
+	assert_eq!(parse_str("a % b"),"(% a b)");
+
+	let lexed = lex_full_text("%{ a => 1, b }");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	assert_eq!(show(&node),"%{a => 1, b => _}");
+	assert!(matches!(node.error.as_deref(),Some(UserSideError::MissingArmArrow(span)) if *span.fragment()=="b"));
+	match &node.base {
+		GrammerNodeBase::Val(Value::Map(map)) => assert!(matches!(map.entries[0].key.base,GrammerNodeBase::Val(Value::Var(_)))),
+		_ => unreachable!("expected a map"),
+	}
+
+	let lexed = lex_full_text("% {1 => 2}");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	let node = node.unwrap();
+	assert_eq!(show(&node),"%{1 => 2}");
+	assert!(matches!(node.error.as_deref(),Some(UserSideError::MapPercentGap(percent,brace)) if *percent.fragment()=="%" && *brace.fragment()=="{"));
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_tuple() {
+	assert_eq!(parse_str("{a, b + 1}"),"{a (+ b 1)}");
+	assert_eq!(parse_str("{}"),"{}");
+	assert_eq!(parse_str("{a}"),"{a}");
+	assert_eq!(parse_str("((a))"),"a");
+	assert_eq!(parse_str("{%ok, [x, {}]} == f(1, {2})"),"(== {%ok [x {}]} f[1 {2}])");
+
+	fn check(input:&str,f:impl Fn(&str,&UserSideError)) {
+		let lexed = lex_full_text(input);
+		let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+		assert!(remaining.input_len()==0,"leftover tokens in {:?}",input);
+		let node = node.unwrap();
+		f(&show(&node),node.error.as_deref().expect("expected an error"));
+	}
+
+	check("()",|_,error| assert!(matches!(error,UserSideError::EmptyGroup(_))));
+	check("(a, b)",|shown,error| {
+		assert_eq!(shown,"{a b}");
+		assert!(matches!(error,UserSideError::ParenTuple(_)));
+	});
+	check("[a,, b]",|shown,error| {
+		assert_eq!(shown,"[a b]");
+		assert!(matches!(error,UserSideError::DuplicateComma(span) if span.location_offset()==3));
+	});
+	check("f(a, b,)",|shown,error| {
+		assert_eq!(shown,"f[a b]");
+		assert!(matches!(error,UserSideError::TrailingComma(span) if span.location_offset()==6));
+	});
+}
+
+#[test]
+#[no_mangle]
+fn test_expr_access() {
+	assert_eq!(parse_str("std::io::print(x.name, a.b.c)"),"std::io::print[(. x name) (. (. a b) c)]");
+	assert_eq!(parse_str("-m::pi * p.x(1)"),"(* (- m::pi) (. p x)[1])");
+
+	let lexed = lex_full_text("std::io::print(x)");
+	let (_,node) = parse_expr(TokenSlice::new(&lexed));
+	match node.unwrap().base {
+		GrammerNodeBase::Val(Value::Call(FunctionCall{func: Func::Pathed(path),..})) => {
+			let offsets = path.segments.iter().map(|x| x.location_offset()).collect::<Vec<_>>();
+			assert_eq!(offsets,[0,5,9]);
+		}
+		_ => unreachable!("expected a call on a path"),
+	}
+
+	let errors = [
+		("a::", "a"),
+		("a.", "(. a _)"),
+		("f(x)::y", "(:: f[x] y)"),
+	];
+	for (input,expected) in errors {
+		let lexed = lex_full_text(input);
+		let (remaining,node) = parse_expr(TokenSlice::new(&lexed));
+		assert!(remaining.input_len()==0);
+		let node = node.unwrap();
+		assert_eq!(show(&node),expected);
+		assert!(node.error.is_some(),"expected an error in {:?}",input);
+	}
+}
@@ -0,0 +1,128 @@
+#![allow(dead_code)]
+
+use crate::ast::{SmallLexToken,Varible,Path,Pattern,Import};
+use nom_locate::LocatedSpan;
+
+/*
+the tree after finalize. everything in here only borrows the source text ('a)
+so the token vector (and its 'b) can be dropped before we start running anything.
+
+unlike GrammerNode there is nothing half parsed in here and no error slots.
+spans are only kept where something can still go wrong at runtime (calls, ops, names).
+*/
+
+#[derive(Debug, PartialEq)]
+pub struct Program<'a> {
+	pub items: Vec<Item<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Item<'a> {
+	Function(Function<'a>),
+	Import(Import<'a>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Function<'a> {
+	pub name: LocatedSpan<&'a str>,
+	pub clauses: Vec<Clause<'a>>, //tried in order, the first whose patterns fit and guard holds is run
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Clause<'a> {
+	pub params: Vec<Pattern<'a>>,
+	pub guard: Option<Expr<'a>>,
+	pub body: Vec<Statement<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Statement<'a> {
+	Expr(Expr<'a>),
+	Assign(Varible<'a>,Expr<'a>),
+	Return(Option<Expr<'a>>), //implicit and explicit returns are the same thing here
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Expr<'a> {
+	Literal(SmallLexToken<'a>),
+	Nil,
+	Var(Varible<'a>),
+	Path(Path<'a>),
+	Member(Box<Expr<'a>>,LocatedSpan<&'a str>),
+	Op(Box<Expr<'a>>,SmallLexToken<'a>,Box<Expr<'a>>),
+	Unary(SmallLexToken<'a>,Box<Expr<'a>>),
+	Call(Box<Expr<'a>>,Vec<Expr<'a>>), //a piped value is already the first argument
+	Concat(Vec<Expr<'a>>), //string interpolation, every part is turned into a string
+
+	If(Box<Expr<'a>>,Vec<Statement<'a>>,Vec<Statement<'a>>), //a missing else is an empty body which returns nil
+	Lamda(Vec<Pattern<'a>>,Vec<Statement<'a>>),
+	Cond(Vec<(Expr<'a>,Expr<'a>)>),
+	Match(Box<Expr<'a>>,Vec<MatchArm<'a>>),
+	For(Vec<ForClause<'a>>,Vec<Statement<'a>>), //collects into an array
+
+	Array(Vec<Expr<'a>>),
+	Range(Box<Expr<'a>>,Box<Expr<'a>>,Option<Box<Expr<'a>>>), //start end step, evaluates to an IntRange
+	Tuple(Vec<Expr<'a>>),
+	Map(Vec<(Expr<'a>,Expr<'a>)>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ForClause<'a> {
+	Generator(Pattern<'a>,Expr<'a>),
+	Filter(Expr<'a>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MatchArm<'a> {
+	pub pattern: Pattern<'a>,
+	pub guard: Option<Expr<'a>>,
+	pub body: Expr<'a>,
+}
+
+//what a range evaluates to. indexed like an array but nothing is stored
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct IntRange {
+	pub start: i64,
+	pub end: i64, //included if the steps land on it
+	pub step: i64,
+}
+
+impl IntRange {
+	//None for a step of 0 which would never reach the end
+	pub fn new(start:i64,end:i64,step:i64) -> Option<Self> {
+		(step != 0).then_some(IntRange{start,end,step})
+	}
+
+	//done in i128 so i64::MIN..i64::MAX does not overflow
+	pub fn len(&self) -> usize {
+		let distance = self.end as i128 - self.start as i128;
+		let step = self.step as i128;
+		if distance != 0 && (distance < 0) != (step < 0) {
+			return 0;
+		}
+		(distance/step + 1).try_into().unwrap_or(usize::MAX)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	//None is out of bounds, same as with arrays
+	pub fn get(&self,i:usize) -> Option<i64> {
+		(i < self.len()).then(|| (self.start as i128 + i as i128 * self.step as i128) as i64)
+	}
+}
+
+#[test]
+#[no_mangle]
+fn test_int_range() {
+	let all = |x:IntRange| (0..x.len()).map(|i| x.get(i).unwrap()).collect::<Vec<_>>();
+	assert_eq!(all(IntRange::new(1,5,1).unwrap()),vec![1,2,3,4,5]);
+	assert_eq!(all(IntRange::new(0,10,3).unwrap()),vec![0,3,6,9]);
+	assert_eq!(all(IntRange::new(5,1,-2).unwrap()),vec![5,3,1]);
+	assert!(IntRange::new(5,1,1).unwrap().is_empty());
+	assert_eq!(IntRange::new(3,3,-1).unwrap().len(),1);
+	assert_eq!(IntRange::new(1,5,1).unwrap().get(5),None);
+	assert_eq!(IntRange::new(i64::MIN,i64::MAX,i64::MAX).unwrap().len(),3);
+	assert!(IntRange::new(1,2,0).is_none());
+}
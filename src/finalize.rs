@@ -0,0 +1,283 @@
+use crate::ast::{GrammerNode,GrammerNodeBase,Value,Func,Lamda,FuncDef,FuncClause,ParenExpr,ListExpr,CondArm,MapEntry,ForClause};
+use crate::final_ast::{self,Program,Item,Function,Clause,Statement,Expr,MatchArm};
+use crate::diagnostics::collect_errors;
+use crate::errors::UserSideError;
+
+/*
+turns a fully parsed tree into the final ast (see final_ast.rs) which does not need the tokens anymore.
+
+only error free trees can be finalized. when there are no errors every non terminal
+(Unprocessed, KeyWord, Paren...) should already be gone, finding one anyway is a bug in the parser.
+*/
+
+#[derive(Debug)]
+pub enum FinalizeError<'a> {
+	User(Vec<UserSideError<'a>>), //every error in the tree in source order
+	Leftover(&'static str), //what kind of node the parser left behind
+}
+
+type Finalized<'a,T> = Result<T,FinalizeError<'a>>;
+
+pub fn finalize<'a>(node:GrammerNode<'a,'_>) -> Finalized<'a,Program<'a>> {
+	let errors = collect_errors(&node);
+	if !errors.is_empty() {
+		return Err(FinalizeError::User(errors));
+	}
+
+	let items = match node.base {
+		GrammerNodeBase::Sequence(nodes) => nodes.into_iter().map(item).collect::<Result<_,_>>()?,
+		base => return Err(leftover(&base)),
+	};
+	Ok(Program{items})
+}
+
+fn leftover<'a>(base:&GrammerNodeBase) -> FinalizeError<'a> {
+	FinalizeError::Leftover(match base {
+		GrammerNodeBase::Unprocessed(_) => "unprocessed tokens",
+		GrammerNodeBase::KeyWord(_) => "keyword",
+		GrammerNodeBase::Paren(_) => "paren",
+		GrammerNodeBase::ControlBlock(_) => "control block",
+		GrammerNodeBase::Else(_) => "else",
+		GrammerNodeBase::Sequence(_) => "sequence",
+		GrammerNodeBase::Val(_) => "value",
+		GrammerNodeBase::Return(_) => "return",
+		GrammerNodeBase::Declare(_) => "assignment",
+		GrammerNodeBase::Function(_) => "function",
+		GrammerNodeBase::Import(_) => "import",
+	})
+}
+
+//for the parts that are only missing when there is an error
+fn missing<'a,T>(x:Option<T>,what:&'static str) -> Finalized<'a,T> {
+	x.ok_or(FinalizeError::Leftover(what))
+}
+
+fn item<'a>(node:GrammerNode<'a,'_>) -> Finalized<'a,Item<'a>> {
+	match node.base {
+		GrammerNodeBase::Function(def) => Ok(Item::Function(function(def)?)),
+		GrammerNodeBase::Import(import) => Ok(Item::Import(import)),
+		base => Err(leftover(&base)),
+	}
+}
+
+fn function<'a>(def:FuncDef<'a,'_>) -> Finalized<'a,Function<'a>> {
+	Ok(Function{
+		name: missing(def.name,"function without a name")?,
+		clauses: def.clauses.into_iter().map(clause).collect::<Result<_,_>>()?,
+	})
+}
+
+fn clause<'a>(clause:FuncClause<'a,'_>) -> Finalized<'a,Clause<'a>> {
+	let guard = match clause.guard {
+		Some(guard) => Some(expr(*missing(guard.condition,"guard without a condition")?)?),
+		None => None,
+	};
+	Ok(Clause{
+		params: clause.params.items,
+		guard,
+		body: body(clause.body)?,
+	})
+}
+
+fn body<'a>(paren:ParenExpr<'a,'_>) -> Finalized<'a,Vec<Statement<'a>>> {
+	let node = match paren.body {
+		Some(node) => node,
+		None => return Ok(Vec::new()),
+	};
+	match node.base {
+		GrammerNodeBase::Sequence(nodes) => nodes.into_iter().map(statement).collect(),
+		base => Err(leftover(&base)),
+	}
+}
+
+fn statement<'a>(node:GrammerNode<'a,'_>) -> Finalized<'a,Statement<'a>> {
+	match node.base {
+		GrammerNodeBase::Val(x) => Ok(Statement::Expr(value(x)?)),
+		GrammerNodeBase::Return(ret) => Ok(Statement::Return(ret.value.map(value).transpose()?)),
+		GrammerNodeBase::Declare(assign) => {
+			let right = missing(assign.right,"assignment without a value")?;
+			Ok(Statement::Assign(assign.left,value(*right)?))
+		}
+		base => Err(leftover(&base)),
+	}
+}
+
+fn expr<'a>(node:GrammerNode<'a,'_>) -> Finalized<'a,Expr<'a>> {
+	match node.base {
+		GrammerNodeBase::Val(x) => value(x),
+		base => Err(leftover(&base)),
+	}
+}
+
+fn boxed<'a>(node:Box<GrammerNode<'a,'_>>) -> Finalized<'a,Box<Expr<'a>>> {
+	expr(*node).map(Box::new)
+}
+
+fn list<'a>(list:ListExpr<'a,'_>) -> Finalized<'a,Vec<Expr<'a>>> {
+	list.items.into_iter().map(|item| expr(item.value)).collect()
+}
+
+fn arrow_arm<'a>(arm:CondArm<'a,'_>) -> Finalized<'a,(Expr<'a>,Expr<'a>)> {
+	let body = missing(arm.body,"arm without a body")?;
+	Ok((expr(*arm.condition)?,expr(*body)?))
+}
+
+fn map_entry<'a>(entry:MapEntry<'a,'_>) -> Finalized<'a,(Expr<'a>,Expr<'a>)> {
+	let value = missing(entry.value,"map entry without a value")?;
+	Ok((expr(*entry.key)?,expr(*value)?))
+}
+
+fn lamda<'a>(lamda:Lamda<'a,'_>) -> Finalized<'a,Expr<'a>> {
+	Ok(Expr::Lamda(lamda.params.items,body(*lamda.body)?))
+}
+
+fn value<'a>(x:Value<'a,'_>) -> Finalized<'a,Expr<'a>> {
+	Ok(match x {
+		Value::Basic(token) => Expr::Literal(token),
+		Value::Nil(_) => Expr::Nil,
+		Value::Var(var) => Expr::Var(var),
+		Value::Path(path) => Expr::Path(path),
+		Value::Member(member) => Expr::Member(boxed(member.value)?,missing(member.field,"member without a field")?),
+		Value::Paren(paren) => expr(*missing(paren.body,"empty group")?)?,
+		Value::Unary(op) => Expr::Unary(
+			op.operator,
+			boxed(missing(op.operand,"prefix operator without an operand")?)?,
+		),
+		Value::Op(op) => Expr::Op(
+			boxed(missing(op.left,"operator without a left side")?)?,
+			op.operator,
+			boxed(missing(op.right,"operator without a right side")?)?,
+		),
+
+		Value::Call(call) => {
+			let func = match call.func {
+				Func::Defed(_) => return Err(FinalizeError::Leftover("call on a def")),
+				Func::Vared(var) => Some(Expr::Var(var)),
+				Func::Pathed(path) => Some(Expr::Path(path)),
+				Func::Anon(x) => Some(lamda(x)?),
+				Func::Computed(node) => Some(expr(*node)?),
+				Func::Concat(_) => None,
+			};
+			let mut args = match call.piped {
+				Some(piped) => vec![value(*piped)?],
+				None => Vec::new(),
+			};
+			args.extend(list(call.args)?);
+			match func {
+				Some(func) => Expr::Call(Box::new(func),args),
+				None => Expr::Concat(args),
+			}
+		}
+
+		Value::If(if_node) => {
+			let condition = missing(if_node.body.start.body,"if without a condition")?;
+			let otherwise = match if_node.else_block {
+				None => Vec::new(),
+				//else if has no braces and its body is the next if
+				Some(else_block) => match else_block.body.start {
+					Some(_) => body(else_block.body)?,
+					None => vec![Statement::Return(Some(expr(*missing(else_block.body.body,"empty else")?)?))],
+				},
+			};
+			Expr::If(boxed(condition)?,body(*if_node.body.body)?,otherwise)
+		}
+		Value::Func(x) => lamda(x)?,
+		Value::Cond(cond) => Expr::Cond(cond.arms.into_iter().map(arrow_arm).collect::<Result<_,_>>()?),
+		Value::Match(match_node) => {
+			let arms = match_node.arms.into_iter().map(|arm| Ok(MatchArm{
+				pattern: arm.pattern,
+				guard: match arm.guard {
+					Some(guard) => Some(expr(*missing(guard.condition,"guard without a condition")?)?),
+					None => None,
+				},
+				body: expr(*missing(arm.body,"arm without a body")?)?,
+			})).collect::<Result<_,_>>()?;
+			Expr::Match(boxed(missing(match_node.value,"match without a value")?)?,arms)
+		}
+
+		Value::For(comprehension) => {
+			let clauses = comprehension.clauses.into_iter().map(|clause| Ok(match clause {
+				ForClause::Generator(generator) => final_ast::ForClause::Generator(
+					generator.pattern,
+					expr(*missing(generator.source,"generator without a source")?)?,
+				),
+				ForClause::Filter(filter) => final_ast::ForClause::Filter(expr(filter)?),
+			})).collect::<Result<_,_>>()?;
+			Expr::For(clauses,body(comprehension.body)?)
+		}
+
+		Value::Range(range) => {
+			let step = match range.step {
+				Some(step) => Some(boxed(missing(step.value,"range without a step")?)?),
+				None => None,
+			};
+			Expr::Range(boxed(range.start)?,boxed(missing(range.end,"range without an end")?)?,step)
+		}
+		Value::Array(x) => Expr::Array(list(x)?),
+		Value::Tuple(x) => Expr::Tuple(list(x)?),
+		Value::Map(map) => Expr::Map(map.entries.into_iter().map(map_entry).collect::<Result<_,_>>()?),
+	})
+}
+
+#[cfg(test)]
+use crate::{lex_full_text,token::TokenSlice,tree::balance,parse::parse,ast::Pattern};
+
+#[cfg(test)]
+fn finalize_text(text:&str) -> Finalized<'_,Program<'_>> {
+	//the tokens are dropped at the end of this function and the program has to outlive them
+	let lexed = balance(lex_full_text(text));
+	let (_,node) = parse(TokenSlice::new(&lexed)).unwrap();
+	finalize(node)
+}
+
+#[test]
+#[no_mangle]
+fn test_finalize() {
+	let program = finalize_text("
+import std::io
+def f(x, _) {
+	y = x |> g(1);
+	if (y > 0) { [y, {1, 2}] } else if (y < 0) { %{1 => -y} } else { nil };
+	fn(z) { z.field }(x)
+}
+").unwrap();
+	assert_eq!(program.items.len(),2);
+	assert!(matches!(&program.items[0],Item::Import(_)));
+
+	let f = match &program.items[1] {
+		Item::Function(f) => f,
+		_ => unreachable!("expected a function"),
+	};
+	assert_eq!(*f.name.fragment(),"f");
+	assert!(f.clauses.len()==1);
+	let f = &f.clauses[0];
+	assert!(matches!(&f.params[..],[Pattern::Bind(x),Pattern::Wildcard(_)] if x.count==1));
+	assert!(f.body.len()==3);
+
+	//the piped value became the first argument
+	match &f.body[0] {
+		Statement::Assign(y,Expr::Call(func,args)) => {
+			assert!(y.count==1);
+			assert!(matches!(&**func,Expr::Var(g) if *g.name.fragment()=="g"));
+			assert!(matches!(&args[..],[Expr::Var(x),Expr::Literal(_)] if x.count==1));
+		}
+		other => unreachable!("expected an assignment got {:?}",other),
+	}
+
+	match &f.body[1] {
+		Statement::Expr(Expr::If(_,then,otherwise)) => {
+			assert!(matches!(&then[..],[Statement::Return(Some(Expr::Array(_)))]));
+			assert!(matches!(&otherwise[..],[Statement::Return(Some(Expr::If(..)))]));
+		}
+		other => unreachable!("expected an if got {:?}",other),
+	}
+	assert!(matches!(&f.body[2],Statement::Return(Some(Expr::Call(..)))));
+
+	match finalize_text("def f() { x = ; }\ndef g(a { a }") {
+		Err(FinalizeError::User(errors)) => {
+			assert!(matches!(&errors[0],UserSideError::MissingRightOperand(_)),"{:?}",errors);
+			assert!(errors.iter().any(|x| matches!(x,UserSideError::UnclosedPar(..))),"{:?}",errors);
+		}
+		other => unreachable!("expected user errors got {:?}",other),
+	}
+}
@@ -1,5 +1,5 @@
 use nom::bytes::complete::{is_a,take_till,take_while,take_while1,tag};
-use nom::sequence::{pair,preceded};
+use nom::sequence::{pair,preceded,terminated,tuple};
 use nom::combinator::recognize;
 use nom::character::complete::{digit1,one_of,anychar};
 
@@ -7,13 +7,14 @@ use nom::multi::fold_many0;
 use nom::branch::alt;	
 use nom::IResult;
 
-// use nom::character::complete::char as nom_char;
+use nom::character::complete::char as nom_char;
 
 use crate::errors::{UserSideError,combine_errors};
-use nom::combinator::{opt};
+use nom::combinator::{opt,not};
 // use nom::bytes::complete::is_not;
 use nom::InputTake;
 use nom::Offset;
+use nom::Slice;
 
 use crate::token::{LexToken,BinaryOp,LexTag};
 use nom_locate::LocatedSpan;
@@ -24,20 +25,37 @@ use nom_locate::LocatedSpan;
 pub fn lex_full_text<'a>(input: &'a str) -> Vec<LexToken<'a>> {
     let mut cursor = LocatedSpan::new(input);
     let mut ans = Vec::new();
-    loop {
-        match lext_text(cursor) {
-            Ok((new_cursor, token)) => {
-                ans.push(token);
-                cursor=new_cursor;
-            }
-            Err(_) => {
-                break;
-            }            
-        }
+    while let Some(new_cursor) = lex_into(cursor, &mut ans) {
+        cursor=new_cursor;
     }
     ans
 }
 
+// Lexes the next token into out. Interpolated strings push a whole sequence at once
+fn lex_into<'a>(input: LocatedSpan<&'a str>, out: &mut Vec<LexToken<'a>>) -> Option<LocatedSpan<&'a str>> {
+    let start = input.location_offset();
+    let input = skip_whitespace_and_comments(input);
+    if let Some(remaining) = lex_interpolated(input, out) {
+        return Some(remaining);
+    }
+    // a : written right after a value is the operator, so a..b:c has the step c and not the atom :c
+    let glued = input.location_offset() == start && out.last().is_some_and(ends_value);
+    let (remaining, token) = match glued && input.fragment().starts_with(':') {
+        true => lex_operator(input).ok()?,
+        false => lext_text(input).ok()?,
+    };
+    out.push(token);
+    Some(remaining)
+}
+
+fn ends_value(token: &LexToken) -> bool {
+    matches!(token.tag,
+        LexTag::Word() | LexTag::Atom() | LexTag::Float(_) | LexTag::Int(_) |
+        LexTag::String(..) | LexTag::RawString(..) | LexTag::StringEnd(_) |
+        LexTag::Delimiter(')' | ']' | '}')
+    )
+}
+
 pub type LexResult<'a> = nom::IResult<LocatedSpan<&'a str>, LexToken<'a>,()>;
 
 #[no_mangle]
@@ -46,6 +64,7 @@ pub fn lext_text<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
 	//TODO add extra token
 	//order is from most common to least
 	alt((
+		lex_raw_string, //before words since it starts with an r
 		lex_word,
         lex_atom,
         lex_ender,
@@ -59,7 +78,7 @@ pub fn lext_text<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
 }
 fn lex_unknowen<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
     let (input,x)=recognize(pair(anychar,take_while(|c:char| !c.is_ascii())))(input)?;
-    Ok((input,LexToken::err_new(x,LexTag::Unknowen(),UserSideError::UnokwenToken(x.clone()))))
+    Ok((input,LexToken::err_new(x,LexTag::Unknowen(),UserSideError::UnokwenToken(x))))
 }
 
 fn lex_atom<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
@@ -73,9 +92,9 @@ fn lex_atom<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
     Ok((input, LexToken::new(ans,LexTag::Atom())))
 }
 
-fn skip_whitespace_and_comments<'a>(input: LocatedSpan<&'a str>) -> LocatedSpan<&'a str> {
+fn skip_whitespace_and_comments(input: LocatedSpan<&str>) -> LocatedSpan<&str> {
     let mut cursor = input;
-    fn lex_comment<'a>(input: LocatedSpan<&'a str>) -> IResult<LocatedSpan<&'a str>,LocatedSpan<&'a str> >{
+    fn lex_comment(input: LocatedSpan<&str>) -> IResult<LocatedSpan<&str>,LocatedSpan<&str> >{
         recognize(preceded(
             is_a("#"),
             take_till(|c| c=='\n'),
@@ -97,13 +116,13 @@ fn skip_whitespace_and_comments<'a>(input: LocatedSpan<&'a str>) -> LocatedSpan<
     cursor
 }
 
-fn skip_whitespace<'a>(input: LocatedSpan<&'a str>) -> LocatedSpan<&'a str> {
-        fn typed_take_whitespace<'a>(input: LocatedSpan<&'a str>) -> IResult<LocatedSpan<&'a str>,LocatedSpan<&'a str>>{
+fn skip_whitespace(input: LocatedSpan<&str>) -> LocatedSpan<&str> {
+        fn typed_take_whitespace(input: LocatedSpan<&str>) -> IResult<LocatedSpan<&str>,LocatedSpan<&str>>{
             take_while( |c:char| c.is_whitespace())(input)
         }
         let s=opt(typed_take_whitespace)(input);
         let(ans,_)=s.unwrap();
-        return ans;
+        ans
     }
 
 fn lex_delimiter<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
@@ -121,6 +140,8 @@ fn lex_ender<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
 fn lex_operator<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
     let (input, token) = alt((
         // 1. Single-char operators with no associated double-char version
+        // (.. goes first so it is not taken as two .)
+        recognize(tag("..")),
         recognize(one_of("+/.%")),
 
         // 2. Multi-character operators
@@ -132,6 +153,7 @@ fn lex_operator<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
         recognize(tag("==")),
         recognize(tag("!=")),
         recognize(tag("<=")),
+        recognize(tag("<-")),
         recognize(tag(">=")),
         recognize(tag("=>")),
         recognize(tag("->")),
@@ -161,6 +183,7 @@ fn lex_operator<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
         "=" => LexTag::Op(BinaryOp::OneEqul),
         
         "." => LexTag::Op(BinaryOp::Dot),
+        ".." => LexTag::Op(BinaryOp::Range),
         "|" => LexTag::Op(BinaryOp::SingleOr),
 
         // Multi-char operators
@@ -171,6 +194,7 @@ fn lex_operator<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
         "==" => LexTag::Op(BinaryOp::TwoEqul),
         "!=" => LexTag::Op(BinaryOp::NotEqual),
         "<=" => LexTag::Op(BinaryOp::SmallerEqual),
+        "<-" => LexTag::Op(BinaryOp::LeftArrow),
         ">=" => LexTag::Op(BinaryOp::BiggerEqual),
         "=>" => LexTag::Op(BinaryOp::FatArrow),
         "->" => LexTag::Op(BinaryOp::SmallArrow),
@@ -203,15 +227,39 @@ fn lex_word<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
 	)		
 }
 
+// r"..." r#"..."# r##"..."## and so on. the string ends at a quote followed by as many # as it opened with.
+// nothing is escaped and it can span lines
+fn lex_raw_string<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
+    let (rest, opener) = recognize(tuple((nom_char('r'), take_while(|c| c == '#'), nom_char('"'))))(input)?;
+    let guards = opener.fragment().len() - 2;
+    let closer = format!("\"{}", "#".repeat(guards));
+
+    match rest.fragment().find(&closer) {
+        Some(i) => {
+            let (remaining, ans) = input.take_split(opener.fragment().len() + i + closer.len());
+            let value = &rest.fragment()[..i];
+            Ok((remaining, LexToken::new(ans, LexTag::RawString(guards, value.into()))))
+        }
+        None => {
+            let (remaining, ans) = input.take_split(input.fragment().len());
+            let eof = ans.slice(ans.fragment().len()..);
+            Ok((remaining, LexToken::err_new(ans,
+                LexTag::PoisonString('"'),
+                UserSideError::UnclosedRawString(opener, eof, guards)
+            )))
+        }
+    }
+}
+
 fn skip_to_str_end(input: &str, del: char) -> Result<usize, usize> {
     assert!(del.is_ascii());
 
     let mut chars = input.chars().peekable();
-    let mut count = 0;
+    let mut count = 0; // in bytes so it can be used to split the span
 
     while let Some(c) = chars.next() {
         // Increment count for each character processed
-        count += 1;
+        count += c.len_utf8();
 
         if c == '\\' {
             // If a backslash is found, skip the next character (escape sequence)
@@ -219,7 +267,7 @@ fn skip_to_str_end(input: &str, del: char) -> Result<usize, usize> {
                 if c2== '\n'{
                     return Err(count);
                 }
-                count += 1; // Count the escaped character as well
+                count += c2.len_utf8(); // Count the escaped character as well
                 continue;
             } else {
                 // If backslash is the last character, return an error with it
@@ -238,14 +286,20 @@ fn skip_to_str_end(input: &str, del: char) -> Result<usize, usize> {
 }
 
 fn lex_string<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
-    let original_input = input.clone();
+    let original_input = input;
 
     let (input,del) = one_of("\"'")(input)?;
+    if input.fragment().starts_with(&String::from_iter([del, del])) {
+        return lex_triple_string(original_input, del);
+    }
+
     match skip_to_str_end(input.fragment(),del) {
         Ok(u) => {
             let (input,ans) = original_input.take_split(u+1);//original del + new stuff
-            Ok((input,LexToken::new(ans,
-                LexTag::String(del))))
+            let (value,error) = cook_string(ans.slice(1..u));
+            let mut token = LexToken::new(ans,LexTag::String(del,value.into()));
+            token.error = error;
+            Ok((input,token))
         }
         Err(u) => {
             let (input,ans) = original_input.take_split(u+1);
@@ -254,13 +308,258 @@ fn lex_string<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a> {
             Ok((input,LexToken::err_new(ans,
                 LexTag::PoisonString(del),
                 UserSideError::UnclosedString(
-                    ans.clone(),del
+                    ans,del
                 )
             )))
         }
     }
 }
 
+// Where the first #{ of a "..." string is, None for strings without one.
+// Scanning the string ourselves is what keeps the # from being taken as a comment
+fn find_hole(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => { chars.next(); }
+            '"' | '\n' => return None,
+            '#' if text[i..].starts_with("#{") => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// "a #{b} c" is lexed as StringStart("a ") { b } StringEnd(" c") so the parser sees normal tokens in the hole.
+// a hole has to be closed on the line it starts on, like the string itself.
+// only "..." strings interpolate, heredocs and '...' are always plain
+fn lex_interpolated<'a>(input: LocatedSpan<&'a str>, out: &mut Vec<LexToken<'a>>) -> Option<LocatedSpan<&'a str>> {
+    let text = *input.fragment();
+    if !text.starts_with('"') || text.starts_with("\"\"\"") {
+        return None;
+    }
+    find_hole(&text[1..])?;
+
+    let mut segment_start = 0; // where the span of the next segment token starts
+    let mut content_start = 1; // where its text starts
+    let mut first = true;
+    let mut pos = 1;
+    loop {
+        let rest = &text[pos..];
+        match rest.chars().next() {
+            Some('\\') => pos += 1 + rest[1..].chars().next().filter(|c| *c != '\n').map_or(0, |c| c.len_utf8()),
+            Some('"') => {
+                let (value, error) = cook_string(input.slice(content_start..pos));
+                let mut token = LexToken::new(input.slice(segment_start..pos + 1), LexTag::StringEnd(value.into()));
+                token.error = error;
+                out.push(token);
+                return Some(input.slice(pos + 1..));
+            }
+            None | Some('\n') => {
+                let (value, error) = cook_string(input.slice(content_start..pos));
+                let mut token = LexToken::new(input.slice(segment_start..pos), LexTag::StringEnd(value.into()));
+                token.error = combine_errors(Some(Box::new(UserSideError::UnclosedString(input.slice(..pos), '"'))), error);
+                out.push(token);
+                return Some(input.slice(pos..));
+            }
+            Some('#') if rest.starts_with("#{") => {
+                let (value, error) = cook_string(input.slice(content_start..pos));
+                let tag = match first {
+                    true => LexTag::StringStart(value.into()),
+                    false => LexTag::StringMiddle(value.into()),
+                };
+                let mut token = LexToken::new(input.slice(segment_start..pos), tag);
+                token.error = error;
+                out.push(token);
+                first = false;
+
+                let open = input.slice(pos..pos + 2);
+                out.push(LexToken::new(open, LexTag::Delimiter('{')));
+                match lex_hole(open, input.slice(pos + 2..), out) {
+                    Ok(after) => {
+                        pos = after.location_offset() - input.location_offset();
+                        segment_start = pos;
+                        content_start = pos;
+                    }
+                    // the rest of the line is lost anyway so the string ends with the hole
+                    Err(remaining) => {
+                        let end = out.last().map(|t| t.span).unwrap_or(open);
+                        let (empty, _) = end.take_split(end.fragment().len());
+                        out.push(LexToken::new(empty, LexTag::StringEnd("".into())));
+                        return Some(remaining);
+                    }
+                }
+            }
+            Some(c) => pos += c.len_utf8(),
+        }
+    }
+}
+
+// Lexes the inside of a #{ up to and including its }. Braces in the hole have to balance.
+// An unclosed hole gets an empty } at the end of its last token that carries the error
+fn lex_hole<'a>(open: LocatedSpan<&'a str>, input: LocatedSpan<&'a str>, out: &mut Vec<LexToken<'a>>) -> Result<LocatedSpan<&'a str>, LocatedSpan<&'a str>> {
+    let mut cursor = input;
+    let mut depth = 0usize;
+    loop {
+        let next = skip_whitespace_and_comments(cursor);
+        if next.fragment().is_empty() || next.location_line() != open.location_line() {
+            let last = out.last().map(|t| t.span).unwrap_or(open);
+            let (empty, _) = last.take_split(last.fragment().len());
+            out.push(LexToken::err_new(empty, LexTag::Delimiter('}'), UserSideError::UnclosedInterpolation(open, last)));
+            return Err(next);
+        }
+        if depth == 0 && next.fragment().starts_with('}') {
+            let (after, close) = next.take_split(1);
+            out.push(LexToken::new(close, LexTag::Delimiter('}')));
+            return Ok(after);
+        }
+
+        let before = out.len();
+        cursor = lex_into(next, out).ok_or(next)?;
+        if out.len() == before + 1 {
+            match out[before].tag {
+                LexTag::Delimiter('{') => depth += 1,
+                LexTag::Delimiter('}') => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+// """ and ''' strings can span lines. Like elixir heredocs a newline right after the opening quotes is dropped
+// and the indentation of the closing quotes (or the smallest one if they are not on their own line) is taken off every line
+fn lex_triple_string<'a>(input: LocatedSpan<&'a str>, del: char) -> LexResult<'a> {
+    let quotes = input.slice(..3);
+    let body = &input.fragment()[3..];
+    let closer = String::from_iter([del; 3]);
+
+    let mut chars = body.char_indices();
+    let mut end = None;
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if body[i..].starts_with(&closer) {
+            end = Some(i);
+            break;
+        }
+    }
+
+    match end {
+        Some(i) => {
+            let (remaining, ans) = input.take_split(i + 6);
+            let (value, error) = cook_triple_string(input.slice(3..i + 3));
+            let mut token = LexToken::new(ans, LexTag::String(del, value.into()));
+            token.error = error;
+            Ok((remaining, token))
+        }
+        None => {
+            let (remaining, ans) = input.take_split(input.fragment().len());
+            let eof = ans.slice(ans.fragment().len()..);
+            Ok((remaining, LexToken::err_new(ans,
+                LexTag::PoisonString(del),
+                UserSideError::UnclosedTripleString(quotes, eof, del)
+            )))
+        }
+    }
+}
+
+fn cook_triple_string(inner: LocatedSpan<&str>) -> (String, Option<Box<UserSideError<'_>>>) {
+    let text = *inner.fragment();
+    let skip = match text.find('\n') {
+        Some(i) if text[..i].trim().is_empty() => i + 1,
+        _ => 0,
+    };
+
+    // (offset, line) with the offsets into inner
+    let mut lines = Vec::new();
+    let mut offset = skip;
+    for line in text[skip..].split('\n') {
+        lines.push((offset, line));
+        offset += line.len() + 1;
+    }
+
+    let indent_of = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let closing_line = lines.len() > 1 && lines.last().is_some_and(|(_, line)| line.trim().is_empty());
+    let indent = match closing_line {
+        true => lines.pop().map(|(_, line)| line.len()).unwrap_or(0),
+        false => lines.iter().filter(|(_, line)| !line.trim().is_empty()).map(|(_, line)| indent_of(line)).min().unwrap_or(0),
+    };
+
+    let mut value = String::with_capacity(text.len());
+    let mut error = None;
+    for (i, (offset, line)) in lines.iter().enumerate() {
+        // lines that are indented less than the rest only lose what they have
+        let strip = indent_of(line).min(indent);
+        let (cooked, line_error) = cook_string(inner.slice(offset + strip..offset + line.len()));
+        value.push_str(&cooked);
+        error = combine_errors(error, line_error);
+        if closing_line || i + 1 < lines.len() {
+            value.push('\n');
+        }
+    }
+
+    (value, error)
+}
+
+// Decodes the escapes in the inside of a string. Bad escapes are kept as written and reported
+fn cook_string(inner: LocatedSpan<&str>) -> (String, Option<Box<UserSideError<'_>>>) {
+    let text = *inner.fragment();
+    let mut value = String::with_capacity(text.len());
+    let mut error = None;
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        let decoded = match chars.next().map(|(_, c)| c) {
+            Some('n') => Some('\n'),
+            Some('t') => Some('\t'),
+            Some('r') => Some('\r'),
+            Some('0') => Some('\0'),
+            Some(c @ ('\\' | '"' | '\'' | '#')) => Some(c),
+            // \xNN is ascii only so a byte is always a full char
+            Some('x') => {
+                let digits: String = (0..2).filter_map(|_| chars.next_if(|(_, c)| c.is_ascii_hexdigit())).map(|(_, c)| c).collect();
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) if digits.len() == 2 && byte.is_ascii() => Some(byte as char),
+                    _ => None,
+                }
+            }
+            // \u{1F600} with 1 to 6 hex digits
+            Some('u') => match chars.next_if(|(_, c)| *c == '{') {
+                None => None,
+                Some(_) => {
+                    let mut digits = String::new();
+                    while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                        digits.push(c);
+                    }
+                    let closed = chars.next_if(|(_, c)| *c == '}').is_some();
+                    match u32::from_str_radix(&digits, 16) {
+                        Ok(code) if closed && digits.len() <= 6 => char::from_u32(code),
+                        _ => None,
+                    }
+                }
+            },
+            _ => None,
+        };
+
+        match decoded {
+            Some(c) => value.push(c),
+            None => {
+                let end = chars.peek().map(|(i, _)| *i).unwrap_or(text.len());
+                let escape = inner.slice(start..end);
+                value.push_str(escape.fragment());
+                error = combine_errors(error, Some(Box::new(UserSideError::InvalidEscape(escape))));
+            }
+        }
+    }
+
+    (value, error)
+}
+
 fn after_dot_to_float(digits: u64) -> f64 {
     if digits == 0 {
         return 0.0;
@@ -277,9 +576,17 @@ fn after_dot_to_float(digits: u64) -> f64 {
 }
 
 fn lex_number<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
-	let (remaining_input, sign_char) = opt(one_of("+-"))(input.clone())?;
-    let (remaining_input, (value,error)) = uint_underscored(remaining_input)?;
-    let (remaining_input,dot) = opt(is_a("."))(remaining_input)?;
+	let (remaining_input, sign_char) = opt(one_of("+-"))(input)?;
+    //0x 0o 0b are always ints
+    let (remaining_input, (value,error), dot) = match uint_radix(remaining_input) {
+        Ok((remaining_input, parsed)) => (remaining_input, parsed, None),
+        Err(_) => {
+            let (remaining_input, parsed) = uint_underscored(remaining_input)?;
+            //1..2 is a range and not the float 1. followed by .2
+            let (remaining_input,dot) = opt(terminated(tag("."),not(tag("."))))(remaining_input)?;
+            (remaining_input, parsed, dot)
+        }
+    };
 
     let sign = match sign_char {
         Some('-') => -1i64,
@@ -298,7 +605,7 @@ fn lex_number<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
 			let signed_value = sign*value.try_into().unwrap_or_else(|_| {
     			error2= Some(Box::new(
     				UserSideError::IntOverflowError(
-            			token_base.clone(), 
+            			token_base, 
             			value
             		)
             	));
@@ -314,7 +621,7 @@ fn lex_number<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
     	Some(_) => {
             let mut fval=value as f64;
 
-    		let remaining_input=match uint_underscored(remaining_input.clone()){
+    		let remaining_input=match uint_underscored(remaining_input){
     			Err(_) => {
     				remaining_input
     			},
@@ -337,14 +644,63 @@ fn lex_number<'a>(input: LocatedSpan<&'a str>) -> LexResult<'a>{
     }
 }
 
-fn uint_underscored<'a>(input: LocatedSpan<&'a str>) -> IResult<LocatedSpan<&'a str>,(u64,Option<Box<UserSideError<'a>>>),()>{
+type UintResult<'a> = IResult<LocatedSpan<&'a str>,(u64,Option<Box<UserSideError<'a>>>),()>;
+
+// 0xFF_FF 0o17 0b1010. everything alphanumeric after the prefix is taken so 0b102 is one bad number
+// and not 0b10 followed by 2
+fn uint_radix(input: LocatedSpan<&str>) -> UintResult<'_> {
+    let (rest, prefix) = recognize(pair(nom_char('0'), one_of("xob")))(input)?;
+    let radix = match prefix.fragment().as_bytes()[1] {
+        b'x' => 16,
+        b'o' => 8,
+        _ => 2,
+    };
+    let (rest, digits) = take_while(|c:char| c.is_alphanumeric() || c=='_')(rest)?;
+
+    let mut value = 0u64;
+    let mut overflowed = false;
+    let mut seen_digit = false;
+    let mut error = None;
+    for (i, c) in digits.fragment().char_indices() {
+        if c == '_' {
+            continue;
+        }
+        let Some(d) = c.to_digit(radix) else {
+            if error.is_none() {
+                error = Some(Box::new(UserSideError::InvalidDigit(digits.slice(i..i + c.len_utf8()), radix)));
+            }
+            continue;
+        };
+        seen_digit = true;
+        if !overflowed {
+            value = value.checked_mul(radix as u64)
+                .and_then(|v| v.checked_add(d as u64))
+                .unwrap_or_else(|| {
+                    overflowed = true;
+                    i64::MAX.try_into().unwrap() //chosen to avoid double overflow reporting
+                });
+        }
+    }
+
+    if !seen_digit && error.is_none() {
+        let end = digits.fragment().len();
+        error = Some(Box::new(UserSideError::InvalidDigit(digits.slice(end..), radix)));
+    }
+    if overflowed {
+        let consumed = input.offset(&rest);
+        error = combine_errors(error, Some(Box::new(UserSideError::OverflowError(input.take(consumed)))));
+    }
+    Ok((rest, (value, error)))
+}
+
+fn uint_underscored(input: LocatedSpan<&str>) -> UintResult<'_>{
 	//rust needs some help on figuring out typing so...
-    fn typed_digit1<'b>(x: LocatedSpan<&'b str>) -> IResult<LocatedSpan<&'b str>, &'b str> {
+    fn typed_digit1(x: LocatedSpan<&str>) -> IResult<LocatedSpan<&str>, &str> {
 	    digit1(x).map(|(i,x)| 
 			(i,*x.fragment())
 		)
 	}
-    let report_input=input.clone();
+    let report_input=input;
     
     let (input,d)=digit1(input)?;
 	
@@ -405,21 +761,21 @@ fn uint_underscored<'a>(input: LocatedSpan<&'a str>) -> IResult<LocatedSpan<&'a
 #[no_mangle]
 fn test_uint_underscored_valid() {
     let input = LocatedSpan::new("111_222_333xyz");
-    let result = uint_underscored(input.clone());
+    let result = uint_underscored(input);
     assert_eq!(
         result,
         Ok((input.take_split(11).0, (111_222_333u64,None)))
     );
 
     let input = LocatedSpan::new("123_6_22 as");
-    let result = uint_underscored(input.clone());
+    let result = uint_underscored(input);
     assert_eq!(
         result,
-        Ok((input.take_split(8).0, (123_6_22u64,None)))
+        Ok((input.take_split(8).0, (123_622u64,None)))
     );
 
     let input = LocatedSpan::new("987654");
-    let result = uint_underscored(input.clone());
+    let result = uint_underscored(input);
     assert_eq!(
         result,
         Ok((input.take_split(6).0, (987654u64,None)))
@@ -431,7 +787,7 @@ fn test_uint_underscored_valid() {
 fn test_lex_int_with_signs() {
     // Test positive number with explicit plus sign
     let input = LocatedSpan::new("+1234");
-    let result = lex_number(input.clone());
+    let result = lex_number(input);
     assert_eq!(
         result,
         Ok((input.take_split(5).0, LexToken::new(input.take_split(5).1,LexTag::Int(1234))))
@@ -439,7 +795,7 @@ fn test_lex_int_with_signs() {
 
     // Test negative number
     let input = LocatedSpan::new("-5678");
-    let result = lex_number(input.clone());
+    let result = lex_number(input);
     assert_eq!(
         result,
         Ok((input.take_split(5).0, LexToken::new(input.take_split(5).1,LexTag::Int(-5678))))
@@ -447,7 +803,7 @@ fn test_lex_int_with_signs() {
 
     // Test number without sign (implicit positive)
     let input = LocatedSpan::new("9876");
-    let result = lex_number(input.clone());
+    let result = lex_number(input);
     assert_eq!(
         result,
         Ok((input.take_split(4).0, LexToken::new(input.take_split(4).1,LexTag::Int(9876))))
@@ -458,19 +814,19 @@ fn test_lex_int_with_signs() {
 #[no_mangle]
 fn test_skip_whitespace() {
     let input = LocatedSpan::new("   xyz");
-    let result = skip_whitespace(input.clone());
+    let result = skip_whitespace(input);
     assert_eq!(result, input.take_split(3).0);
 
     let input = LocatedSpan::new("\t\t123_6_22 as");
-    let result = skip_whitespace(input.clone());
+    let result = skip_whitespace(input);
     assert_eq!(result, input.take_split(2).0);
 
     let input = LocatedSpan::new("\n!!!");
-    let result = skip_whitespace(input.clone());
+    let result = skip_whitespace(input);
     assert_eq!(result, input.take_split(1).0);
 
     let input = LocatedSpan::new("987654");
-    let result = skip_whitespace(input.clone());
+    let result = skip_whitespace(input);
     assert_eq!(result, input); // No whitespace to skip, so it should return the original input
 }
 
@@ -572,9 +928,31 @@ fn test_multi_char_operators() {
     assert_operator("==", LexTag::Op(BinaryOp::TwoEqul));
     assert_operator("!=", LexTag::Op(BinaryOp::NotEqual));
     assert_operator("<=", LexTag::Op(BinaryOp::SmallerEqual));
+    assert_operator("<-", LexTag::Op(BinaryOp::LeftArrow));
     assert_operator(">=", LexTag::Op(BinaryOp::BiggerEqual));
     assert_operator("=>", LexTag::Op(BinaryOp::FatArrow));
     assert_operator("->", LexTag::Op(BinaryOp::SmallArrow));
+    assert_operator("..", LexTag::Op(BinaryOp::Range));
+}
+
+#[test]
+#[no_mangle]
+fn test_lex_range() {
+    let tags = |text| lex_full_text(text).into_iter().map(|t| t.tag).collect::<Vec<_>>();
+    assert_eq!(tags("1..2"), vec![LexTag::Int(1), LexTag::Op(BinaryOp::Range), LexTag::Int(2)]);
+    assert_eq!(tags("1.5..x"), vec![LexTag::Float(1.5), LexTag::Op(BinaryOp::Range), LexTag::Word()]);
+    assert_eq!(tags("0..10:2"), vec![
+        LexTag::Int(0), LexTag::Op(BinaryOp::Range), LexTag::Int(10),
+        LexTag::Op(BinaryOp::Dots), LexTag::Int(2),
+    ]);
+    assert_eq!(tags("a..b:c"), vec![
+        LexTag::Word(), LexTag::Op(BinaryOp::Range), LexTag::Word(),
+        LexTag::Op(BinaryOp::Dots), LexTag::Word(),
+    ]);
+    assert_eq!(tags("f(:ok) :c"), vec![
+        LexTag::Word(), LexTag::Delimiter('('), LexTag::Atom(), LexTag::Delimiter(')'), LexTag::Atom(),
+    ]);
+    assert_eq!(tags("1."), vec![LexTag::Float(1.0)]);
 }
 
 #[test]
@@ -582,16 +960,16 @@ fn test_multi_char_operators() {
 fn test_lex_string() {
     // Test a valid string with escaped characters
     let input = LocatedSpan::new("\"Hello, world!\\n\" junk");
-    let result = lex_string(input.clone());
+    let result = lex_string(input);
     assert!(result.is_ok(), "Failed to parse valid string");
     let (remaining, token) = result.unwrap();
-    assert_eq!(token.tag, LexTag::String('"'));
+    assert_eq!(token.tag, LexTag::String('"', "Hello, world!\n".into()));
     assert_eq!(token.span.fragment(), &"\"Hello, world!\\n\"");
     assert_eq!(remaining.fragment().len(), 5, "Unexpected characters remaining after parsing a valid string");
 
     // Test an unclosed string which should still return a token and log an error
     let input = LocatedSpan::new("\"Unclosed string example");
-    let result = lex_string(input.clone());
+    let result = lex_string(input);
     assert!(result.is_ok(), "Should return a token despite being unclosed");
     let (_, token) = result.unwrap();
     assert_eq!(token.tag, LexTag::PoisonString('"'));
@@ -600,32 +978,199 @@ fn test_lex_string() {
 
     // Test a single character string
     let input = LocatedSpan::new("'a'");
-    let result = lex_string(input.clone());
+    let result = lex_string(input);
     assert!(result.is_ok(), "Failed to parse single character string");
     let (remaining, token) = result.unwrap();
-    assert_eq!(token.tag, LexTag::String('\''));
+    assert_eq!(token.tag, LexTag::String('\'', "a".into()));
     assert_eq!(token.span.fragment(), &"'a'");
     assert_eq!(remaining.fragment().len(), 0, "Unexpected characters remaining after parsing a single character string");
 
     // Test a string with special escaped characters
     let input = LocatedSpan::new("\"Escaped \\\" quote\"");
-    let result = lex_string(input.clone());
+    let result = lex_string(input);
     assert!(result.is_ok(), "Failed to parse string with escaped quote");
     let (remaining, token) = result.unwrap();
-    assert_eq!(token.tag, LexTag::String('"'));
+    assert_eq!(token.tag, LexTag::String('"', "Escaped \" quote".into()));
     assert_eq!(token.span.fragment(), &"\"Escaped \\\" quote\"");
     assert_eq!(remaining.fragment().len(), 0, "Unexpected characters remaining after parsing a string with escaped quote");
 
     // Test a string containing newlines and tabs
     let input = LocatedSpan::new("\"Line1\\nLine2\\tTabbed\"");
-    let result = lex_string(input.clone());
+    let result = lex_string(input);
     assert!(result.is_ok(), "Failed to parse string with newlines and tabs");
     let (remaining, token) = result.unwrap();
-    assert_eq!(token.tag, LexTag::String('"'));
+    assert_eq!(token.tag, LexTag::String('"', "Line1\nLine2\tTabbed".into()));
     assert_eq!(token.span.fragment(), &"\"Line1\\nLine2\\tTabbed\"");
     assert_eq!(remaining.fragment().len(), 0, "Unexpected characters remaining after parsing a string with newlines and tabs");
 }
 
+#[test]
+#[no_mangle]
+fn test_lex_string_escapes() {
+    let cooked = |text| match lex_full_text(text).remove(0) {
+        LexToken { tag: LexTag::String(_, value), error: None, .. } => value,
+        token => unreachable!("expected a clean string got {:?}", token),
+    };
+    assert_eq!(&*cooked(r#""a\tb\\c\"d\'e\0""#), "a\tb\\c\"d'e\0");
+    assert_eq!(&*cooked(r#"'\x41\u{1F600}\u{e9}'"#), "A\u{1F600}\u{e9}");
+
+    // every bad escape is reported on its own with the exact text
+    let tokens = lex_full_text(r#""ok \q \x4 \xFF \u{110000} \u41 end""#);
+    assert_eq!(tokens.len(), 1);
+    let escapes: Vec<_> = match tokens[0].error.as_deref() {
+        Some(UserSideError::Compound(errors)) => errors.iter().map(|e| match e {
+            UserSideError::InvalidEscape(span) => *span.fragment(),
+            other => unreachable!("expected an escape error got {:?}", other),
+        }).collect(),
+        other => unreachable!("expected several errors got {:?}", other),
+    };
+    assert_eq!(escapes, vec!["\\q", "\\x4", "\\xFF", "\\u{110000}", "\\u"]);
+    match &tokens[0].error.as_deref() {
+        Some(UserSideError::Compound(errors)) => match &errors[0] {
+            UserSideError::InvalidEscape(span) => assert_eq!(span.location_offset(), 4),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+#[no_mangle]
+fn test_lex_unicode_string() {
+    let tokens = lex_full_text("x = \"héllo wörld 😀\" + 1");
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(tokens[2].tag, LexTag::String('"', "héllo wörld 😀".into()));
+    assert_eq!(*tokens[2].span.fragment(), "\"héllo wörld 😀\"");
+
+    let tokens = lex_full_text("x = 'ü\ny");
+    assert_eq!(tokens[2].tag, LexTag::PoisonString('\''));
+    assert!(matches!(tokens[2].error.as_deref(), Some(UserSideError::UnclosedString(..))));
+    assert_eq!(*tokens[3].span.fragment(), "y");
+    assert_eq!(lex_full_text("'ü").len(), 1);
+}
+
+#[test]
+#[no_mangle]
+fn test_lex_triple_string() {
+    let cooked = |text| match lex_full_text(text).remove(0) {
+        LexToken { tag: LexTag::String(_, value), error: None, .. } => value,
+        token => unreachable!("expected a clean string got {:?}", token),
+    };
+    let heredoc = "x = \"\"\"\n    first\n      indented\\t\n\n    last\n    \"\"\"";
+    let tokens = lex_full_text(heredoc);
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(tokens[2].tag, LexTag::String('"', "first\n  indented\t\n\nlast\n".into()));
+
+    // without a line of its own for the closing quotes the smallest indentation is used
+    assert_eq!(&*cooked("\'\'\'\n\t\ta\n\tb\'\'\'"), "\ta\nb");
+    assert_eq!(&*cooked("\"\"\"one line\"\"\""), "one line");
+    assert_eq!(&*cooked("\"\"\"a \\\"\"\" b\"\"\""), "a \"\"\" b");
+    assert_eq!(&*cooked("\"\""), "");
+
+    let text = "f(\"\"\"\n  never closed\n  )\n";
+    let tokens = lex_full_text(text);
+    let token = tokens.last().unwrap();
+    assert_eq!(token.tag, LexTag::PoisonString('"'));
+    match token.error.as_deref() {
+        Some(UserSideError::UnclosedTripleString(start, end, '"')) => {
+            assert_eq!(*start.fragment(), "\"\"\"");
+            assert_eq!(start.location_offset(), 2);
+            assert_eq!(end.location_offset(), text.len());
+        }
+        other => unreachable!("expected an unclosed string got {:?}", other),
+    }
+}
+
+#[test]
+#[no_mangle]
+fn test_lex_raw_string() {
+    let tags = |text| lex_full_text(text).into_iter().map(|t| t.tag).collect::<Vec<_>>();
+    assert_eq!(tags(r#"r"C:\new\#{x}""#), vec![LexTag::RawString(0, r"C:\new\#{x}".into())]);
+    assert_eq!(tags(r###"r##"say "#hi"#"## r"###), vec![
+        LexTag::RawString(2, r##"say "#hi"#"##.into()),
+        LexTag::Word(),
+    ]);
+    // a lone r is still a name and the # after it a comment
+    assert_eq!(tags("r #\"x\""), vec![LexTag::Word()]);
+
+    let text = "x = r##\"a\"#\nb";
+    let token = lex_full_text(text).pop().unwrap();
+    assert_eq!(token.tag, LexTag::PoisonString('"'));
+    match token.error.as_deref() {
+        Some(UserSideError::UnclosedRawString(start, end, 2)) => {
+            assert_eq!(*start.fragment(), "r##\"");
+            assert_eq!(end.location_offset(), text.len());
+        }
+        other => unreachable!("expected an unclosed raw string got {:?}", other),
+    }
+}
+
+#[test]
+#[no_mangle]
+fn test_lex_interpolation() {
+    let tags = |text| lex_full_text(text).into_iter().map(|t| t.tag).collect::<Vec<_>>();
+    assert_eq!(tags("\"hi #{name}! # not a comment\""), vec![
+        LexTag::StringStart("hi ".into()),
+        LexTag::Delimiter('{'),
+        LexTag::Word(),
+        LexTag::Delimiter('}'),
+        LexTag::StringEnd("! # not a comment".into()),
+    ]);
+
+    // braces inside the hole have to balance before it closes
+    let tokens = lex_full_text("\"#{ %{1 => 2} }\\#{x}\" + 1");
+    assert_eq!(tokens[0].tag, LexTag::StringStart("".into()));
+    assert_eq!(*tokens[1].span.fragment(), "#{");
+    assert_eq!(tokens[8].tag, LexTag::Delimiter('}'));
+    assert_eq!(tokens[9].tag, LexTag::StringEnd("#{x}".into()));
+    assert_eq!(tokens[10].tag, LexTag::Op(BinaryOp::Add));
+
+    // nested strings are lexed by the hole like any other token
+    assert_eq!(tags("\"a#{\"b#{c}\"}\"").len(), 9);
+
+    let text = "x = \"a #{f(1)\ny";
+    let tokens = lex_full_text(text);
+    let close = &tokens[tokens.len() - 3];
+    assert_eq!(close.tag, LexTag::Delimiter('}'));
+    match close.error.as_deref() {
+        Some(UserSideError::UnclosedInterpolation(open, last)) => {
+            assert_eq!(open.location_offset(), 7);
+            assert_eq!(*last.fragment(), ")");
+        }
+        other => unreachable!("expected an unclosed interpolation got {:?}", other),
+    }
+    assert_eq!(tokens[tokens.len() - 2].tag, LexTag::StringEnd("".into()));
+    assert_eq!(*tokens.last().unwrap().span.fragment(), "y");
+}
+
+#[test]
+#[no_mangle]
+fn test_lex_radix_numbers() {
+    let lex_one = |text| lex_number(LocatedSpan::new(text)).unwrap().1;
+    assert_eq!(lex_one("0xFF_ff").tag, LexTag::Int(0xFFFF));
+    assert_eq!(lex_one("-0o17").tag, LexTag::Int(-0o17));
+    assert_eq!(lex_one("0b1010_0001").tag, LexTag::Int(0b1010_0001));
+    assert_eq!(lex_one("0x7fff_ffff_ffff_ffff").tag, LexTag::Int(i64::MAX));
+
+    // a . after a hex number is never a float
+    let tags = lex_full_text("0x10.y").into_iter().map(|t| t.tag).collect::<Vec<_>>();
+    assert_eq!(tags, vec![LexTag::Int(16), LexTag::Op(BinaryOp::Dot), LexTag::Word()]);
+
+    let token = lex_one("0b102");
+    assert_eq!(*token.span.fragment(), "0b102");
+    match token.error.as_deref() {
+        Some(UserSideError::InvalidDigit(digit, 2)) => {
+            assert_eq!(*digit.fragment(), "2");
+            assert_eq!(digit.location_offset(), 4);
+        }
+        other => unreachable!("expected an invalid digit got {:?}", other),
+    }
+    assert!(matches!(lex_one("0x").error.as_deref(), Some(UserSideError::InvalidDigit(digit, 16)) if digit.fragment().is_empty()));
+
+    assert!(matches!(lex_one("0x8000_0000_0000_0000").error.as_deref(), Some(UserSideError::IntOverflowError(_, _))));
+    assert!(matches!(lex_one("0x1_0000_0000_0000_0000").error.as_deref(), Some(UserSideError::OverflowError(_))));
+}
+
 #[test]
 #[no_mangle]
 fn test_overflow_errors() {
@@ -638,7 +1183,7 @@ fn test_overflow_errors() {
     let input_overflow_with_underscores = LocatedSpan::new("9999999999_9999999999_9999999999");
 
     // Test large float overflow
-    let result_large_float = lex_number(input_large_float.clone());
+    let result_large_float = lex_number(input_large_float);
     assert!(result_large_float.is_ok(), "Failed to parse large float with overflow");
     let (_, token_large_float) = result_large_float.unwrap();
     assert!(matches!(token_large_float.tag, LexTag::Float(_)), "Expected a float token despite overflow");
@@ -646,7 +1191,7 @@ fn test_overflow_errors() {
     assert!(matches!(token_large_float.error.as_ref().unwrap().as_ref(), UserSideError::Compound(_)), "Expected 2 Errors ");
 
     // Test large int overflow
-    let result_large_int = lex_number(input_large_int.clone());
+    let result_large_int = lex_number(input_large_int);
     assert!(result_large_int.is_ok(), "Failed to parse large int with overflow");
     let (_, token_large_int) = result_large_int.unwrap();
     assert!(matches!(token_large_int.tag, LexTag::Int(_)), "Expected an int token despite overflow");
@@ -654,14 +1199,14 @@ fn test_overflow_errors() {
     assert!(matches!(token_large_int.error.as_ref().unwrap().as_ref(), UserSideError::IntOverflowError(_, _)), "Expected IntOverflowError");
 
     // Test number with underscores
-    let result_with_underscores = lex_number(input_with_underscores.clone());
+    let result_with_underscores = lex_number(input_with_underscores);
     assert!(result_with_underscores.is_ok(), "Failed to parse number with underscores");
     let (_, token_with_underscores) = result_with_underscores.unwrap();
     assert_eq!(*token_with_underscores.span.fragment(), "2_33_1", "Parsed value should ignore underscores");
     assert!(token_with_underscores.error.is_none(), "Unexpected error for valid number with underscores");
 
     // Test overflow with underscores
-    let result_overflow_with_underscores = lex_number(input_overflow_with_underscores.clone());
+    let result_overflow_with_underscores = lex_number(input_overflow_with_underscores);
     assert!(result_overflow_with_underscores.is_ok(), "Failed to parse overflow number with underscores");
     let (_, token_overflow_with_underscores) = result_overflow_with_underscores.unwrap();
     assert!(matches!(token_overflow_with_underscores.tag, LexTag::Int(_)), "Expected an int token despite overflow");
@@ -684,7 +1229,7 @@ fn test_lex_text_happy_path() {
         LexTag::Float(2.112),
         LexTag::Ender(';'),
         LexTag::Float(1.0),
-        LexTag::String('"'),                // '"string"'
+        LexTag::String('"', "string".into()), // '"string"'
         // LexTag::Comment(),                  // '# comment'
         LexTag::Atom(),                     // '%atom'
         LexTag::Op(BinaryOp::DoubleDots),
@@ -1,18 +1,32 @@
 use std::env;
 use std::str::FromStr;
 use std::time::Instant;
+use std::thread;
 
 mod lex;
 mod errors;
 mod token;
 
 mod parse;
+mod expr;
+mod pattern;
 mod ast;
+mod scope;
+mod diagnostics;
+mod tree;
+mod parallel;
+mod final_ast;
+mod finalize;
 
 mod reporting;
 
 use crate::lex::lex_full_text;
 use crate::reporting::print_errors_to_stdout;
+use crate::token::TokenSlice;
+use crate::parse::parse;
+use crate::finalize::{finalize, FinalizeError};
+use crate::tree::balance;
+use crate::parallel::parse_parallel;
 
 use std::fs::File;
 use std::io::{Read, stdout, Write};
@@ -35,7 +49,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Start the timer
 
         // Run the sample
-        run_on_sample()?;
+        run_on_sample(i)?;
 
         // Stop the timer
         let duration = start.elapsed();
@@ -47,12 +61,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn run_on_sample() -> Result<(), Box<dyn std::error::Error>> {
+fn run_on_sample(iteration: usize) -> Result<(), Box<dyn std::error::Error>> {
     // Specify the path to the file you want to lex
     let path = Path::new("sample.txt");
     
     // Open the file
-    let mut file = File::open(&path)?;
+    let mut file = File::open(path)?;
 
     // Read the file content into a string
     let mut content = String::new();
@@ -60,18 +74,52 @@ fn run_on_sample() -> Result<(), Box<dyn std::error::Error>> {
 
     let code = &content;
 
-    let mut errors = Vec::new();
-
-    // Create a Cursor from the content
-    for token in lex_full_text(code) {
+    let tokens = balance(lex_full_text(code));
+    for token in tokens.iter() {
         println!("{:?}", token);
-        if let Some(e) = token.error {
-            errors.push(*e);
-        }
     }
 
+    // Parse both ways so the two can be compared.
+    // Whichever runs second gets warm caches so the order flips every iteration
+    let threads = thread::available_parallelism().map(|x| x.get()).unwrap_or(1);
+    let time_serial = || {
+        let start = Instant::now();
+        parse(TokenSlice::new(&tokens)).map(|(_, tree)| (tree, start.elapsed()))
+    };
+    let time_parallel = || {
+        let start = Instant::now();
+        parse_parallel(TokenSlice::new(&tokens), threads).map(|(_, tree)| (tree, start.elapsed()))
+    };
+    let (serial, parallel) = match iteration % 2 {
+        1 => {
+            let serial = time_serial();
+            (serial, time_parallel())
+        }
+        _ => {
+            let parallel = time_parallel();
+            (time_serial(), parallel)
+        }
+    };
+    let (tree, serial_time) = serial.map_err(|_| "parse failed")?;
+    let (parallel_tree, parallel_time) = parallel.map_err(|_| "parse failed")?;
 
-    print_errors_to_stdout(&errors,code)?;
+    if tree != parallel_tree {
+        return Err("parallel parse gave a different tree".into());
+    }
+    let first = if iteration % 2 == 1 { "serial" } else { "parallel" };
+    println!("Parse serial: {:?} parallel ({} threads): {:?} ({} ran first)", serial_time, threads, parallel_time, first);
+
+    // Lex errors are carried over into the tree so finalizing checks both
+    match finalize(tree) {
+        Ok(program) => {
+            // The program only borrows the source text so the tokens can go now
+            drop(parallel_tree);
+            drop(tokens);
+            println!("Finalized {} items", program.items.len());
+        }
+        Err(FinalizeError::User(errors)) => print_errors_to_stdout(&errors, code)?,
+        Err(FinalizeError::Leftover(what)) => return Err(format!("parser left a {} in an error free tree", what).into()),
+    }
     stdout().flush()?;
 
     Ok(())
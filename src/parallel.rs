@@ -0,0 +1,73 @@
+use crate::token::TokenSlice;
+use crate::ast::GrammerNode;
+use crate::parse::{GResult,split_outer,parse_segment,merge_clauses};
+
+use std::sync::atomic::{AtomicUsize,Ordering};
+use std::thread;
+
+use nom::{InputLength,InputTake};
+
+/*
+the top level pieces (every def and import) do not depend on each other so they can be parsed on different threads.
+workers grab the next piece off a shared atomic counter so a thread that got a small function just takes another one.
+every result keeps its index and they are put back in source order before clauses are merged,
+so the tree and the order of errors are exactly the same as what parse gives.
+*/
+
+pub fn parse_parallel<'a,'b>(input:TokenSlice<'a,'b>,threads:usize) -> GResult<'a,'b> {
+	let pieces = split_outer(&input);
+	let threads = threads.clamp(1,pieces.len().max(1));
+
+	let next = AtomicUsize::new(0);
+	let work = || {
+		let mut done = Vec::new();
+		loop {
+			let i = next.fetch_add(1,Ordering::Relaxed);
+			match pieces.get(i) {
+				Some(piece) => done.push((i,parse_segment(piece.clone()))),
+				None => return done,
+			}
+		}
+	};
+
+	let mut slots :Vec<Option<GrammerNode<'a,'b>>> = (0..pieces.len()).map(|_| None).collect();
+	thread::scope(|s| {
+		let workers :Vec<_> = (1..threads).map(|_| s.spawn(work)).collect();
+		//this thread works too instead of just waiting
+		let mine = work();
+		for (i,node) in workers.into_iter().flat_map(|x| x.join().unwrap()).chain(mine) {
+			slots[i] = Some(node);
+		}
+	});
+
+	let nodes :Vec<_> = slots.into_iter().flatten().collect();
+	let (input,_) = input.take_split(input.input_len());
+	Ok((input,merge_clauses(nodes).into()))
+}
+
+#[cfg(test)]
+use crate::{lex_full_text,tree::balance,parse::parse,diagnostics::collect_errors};
+
+#[test]
+#[no_mangle]
+fn test_parse_parallel() {
+	let mut text = String::from("junk ;\nimport a::b\n");
+	for i in 0..50 {
+		text.push_str(&format!("def f{i}(x) {{ y = x + {i}; g(y, ) }}\n"));
+		if i % 7 == 0 {
+			text.push_str(&format!("def broken{i}(x {{ x\n"));
+		}
+	}
+
+	let lexed = balance(lex_full_text(&text));
+	let (_,serial) = parse(TokenSlice::new(&lexed)).unwrap();
+	for threads in [1,2,4,16] {
+		let (remaining,parallel) = parse_parallel(TokenSlice::new(&lexed),threads).unwrap();
+		assert!(remaining.input_len()==0);
+		assert!(parallel == serial,"different tree with {} threads",threads);
+		assert_eq!(collect_errors(&parallel),collect_errors(&serial));
+	}
+
+	let (_,empty) = parse_parallel(TokenSlice::new(&[]),4).unwrap();
+	assert!(collect_errors(&empty).is_empty());
+}
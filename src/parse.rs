@@ -1,73 +1,117 @@
-use crate::token::{TokenSlice,LexToken,LexTag};
-use crate::ast::{GrammerNode,GrammerNodeBase,ParenExpr,Value,KeyWord,Block};
+use crate::token::{TokenSlice,LexToken,LexTag,BinaryOp};
+use crate::scope::number_bindings;
+use crate::tree::{token_trees,TokenTree};
+use crate::ast::{GrammerNode,GrammerNodeBase,ParenExpr,KeyWord,Block,FuncDef,FuncClause,PatternList,Guard,Import,Return,Assign,Varible};
+use crate::expr::{parse_expr,parse_group,is_guard};
+use crate::pattern::parse_pattern_list;
 use nom::IResult;
-use crate::errors::{UserSideError};
+use crate::errors::{UserSideError,combine_errors};
 use nom_locate::LocatedSpan;
 
-use nom::sequence::tuple;
-use nom::combinator::opt;
-
 use nom::bytes::complete::take;
-use nom::bytes::complete::{take_till,take_while};
-use nom::InputLength;
-use crate::ast::FuncDef;
+use nom::{InputLength,InputIter,InputTake,Slice};
 
 use nom::{Err::Error};
 
-// // use crate::errors::TResult;
-fn is_paren<'a>(x:&LexToken<'a>) -> bool {
-	match x.tag {
-		LexTag::Delimiter(_) => true,
-		_ => false,
-	}
+fn is_paren(x:&LexToken) -> bool {
+	matches!(x.tag,LexTag::Delimiter(_))
 }
 
-// enum AssumeResult<'a,'b> {
-// 	None,
-// 	Wrong(TokenSlice<'a,'b>,TokenSlice<'a,'b>),
-// 	Valid(TokenSlice<'a,'b>,TokenSlice<'a,'b>),
-// }
+pub fn peek<'a,'b>(input:&TokenSlice<'a,'b>) -> Option<&'b LexToken<'a>> {
+	input.iter_elements().next()
+}
 
-fn is_opener(c:char) -> bool {
-	match c {
-		'{' => true,
-		'[' => true,
-		'(' => true,
+pub fn is_op(x:&LexToken,op:BinaryOp) -> bool {
+	x.tag == LexTag::Op(op)
+}
+
+pub fn is_delimiter(x:&LexToken,c:char) -> bool {
+	x.tag == LexTag::Delimiter(c)
+}
 
-		')' => false,
-		']' => false,
-		'}' => false,
+fn is_ender(x:&LexToken,c:char) -> bool {
+	x.tag == LexTag::Ender(c)
+}
+
+pub fn is_opener(c:char) -> bool {
+	matches!(c,'{' | '[' | '(')
+}
+
+pub fn get_closer(c:char) -> char {
+	match c {
+		'{' => '}',
+		'[' => ']',
+		'(' => ')',
 
-		_ => unreachable!()
+		_ => c
 	}
 }
 
-// fn get_closer(c:char) -> char {
-// 	match c {
-// 		'{' => '}',
-// 		'[' => ']',
-// 		'(' => ')',
+//junk we could not make sense of. tokens that already failed to lex keep their own errors
+pub fn unexpected_tokens<'a>(junk:&TokenSlice<'a,'_>) -> UserSideError<'a> {
+	let mut errors :Vec<UserSideError<'a>> = junk.iter_elements()
+		.filter_map(|t| t.error.as_deref().cloned())
+		.collect();
+
+	//if every token is already reported there is nothing to add
+	if errors.len() < junk.input_len() {
+		errors.insert(0,UserSideError::UnexpectedTokens(junk.spans()));
+	}
+	match errors.len() {
+		1 => errors.pop().unwrap(),
+		_ => UserSideError::Compound(errors),
+	}
+}
 
-// 		_ => unreachable!()
-// 	}
-// }
 pub type TResult<'a,'b,T> = nom::IResult<TokenSlice<'a,'b>, T, ()>;
 pub type GResult<'a,'b> = nom::IResult<TokenSlice<'a,'b>, GrammerNode<'a,'b>,()>;
-pub type RawResult<'a,'b> = nom::IResult<TokenSlice<'a,'b>, TokenSlice<'a,'b>,()>;
 
-#[allow(dead_code)]
+//parsers that never fail return the remaining input, the result and whatever went wrong on the way
+pub type ParenResult<'a,'b> = (TokenSlice<'a,'b>,ParenExpr<'a,'b>,Option<Box<UserSideError<'a>>>);
+
+//never fails. anything we cant make sense of ends up as an error on the closest node
+//and we pick up again at the next ;, } or outer keyword
 pub fn parse<'a,'b>(input:TokenSlice<'a,'b>) -> GResult<'a,'b> {
-	// let start :GrammerNode<'a,'b> = GrammerNodeBase::Unprocessed(input).into();
-	// start
-	if input.input_len() == 0 {
-		return return Err(Error(()));
+	let nodes :Vec<_> = split_outer(&input).into_iter().map(parse_segment).collect();
+	let (input,_) = input.take_split(input.input_len());
+	Ok((input,merge_clauses(nodes).into()))
+}
+
+//the top level is split at outer keywords that are not inside any paren.
+//every piece can be parsed on its own which is what parse_parallel relies on
+pub fn split_outer<'a,'b>(input:&TokenSlice<'a,'b>) -> Vec<TokenSlice<'a,'b>> {
+	let mut pieces = Vec::new();
+	let mut rest = input.clone();
+	let mut len = 0;
+	for tree in token_trees(input.clone()) {
+		//a piece starts with its keyword (or is the junk before the first one)
+		if len > 0 && matches!(tree,TokenTree::Leaf(token) if is_outer_keyword(token)) {
+			let (remaining,piece) = rest.take_split(len);
+			pieces.push(piece);
+			rest = remaining;
+			len = 0;
+		}
+		len += tree.len();
+	}
+	if rest.input_len() > 0 {
+		pieces.push(rest);
 	}
+	pieces
+}
+
+//a single piece from split_outer
+pub fn parse_segment<'a,'b>(input:TokenSlice<'a,'b>) -> GrammerNode<'a,'b> {
 	match parse_outer_scope(input.clone()) {
-		Err(_) => Ok((TokenSlice::new(&[]),GrammerNodeBase::Unprocessed(input).into())),
-		Ok((input,res)) => {
-			let x = handle_outer(res);
-			todo!()
-		}//Some(handle_outer(input,res)),
+		Ok((_,outer)) => {
+			let mut node = handle_outer(outer);
+			number_bindings(&mut node);
+			node
+		}
+		//junk before the first outer keyword
+		Err(_) => {
+			let node :GrammerNode<'a,'b> = GrammerNodeBase::Unprocessed(input.clone()).into();
+			node.with_error(unexpected_tokens(&input))
+		}
 	}
 }
 
@@ -88,16 +132,13 @@ fn parse_outer_keyword<'a,'b>(input:TokenSlice<'a,'b>) -> IResult<TokenSlice<'a,
 	Ok((input,keyword))
 }
 
-fn is_outer_keyword<'a,'b>(token:&LexToken<'a>) -> bool{
+fn is_outer_keyword(token:&LexToken) -> bool{
 	match token.tag {
 		LexTag::Word() => {},
 		_ => {return false}
 	};
 
-	match *token.span.fragment() {
-		"def" | "import"=> true,
-		_ => false
-	}
+	matches!(*token.span.fragment(),"def" | "import")
 }
 
 struct OuterExp<'a,'b> {
@@ -107,14 +148,15 @@ struct OuterExp<'a,'b> {
 
 fn parse_outer_scope<'a,'b>(input:TokenSlice<'a,'b>) -> TResult<'a,'b,OuterExp<'a,'b>> {
 	let (input,word) = parse_outer_keyword(input)?;
-	let (input,remainder) = take_till(is_outer_keyword)(input)?;
+	//keywords inside a paren do not count. the delimiter pass already closed anything left open
+	let (input,remainder) = skip_till(input,is_outer_keyword);
 
 	Ok((input,OuterExp{keyword: word,body: remainder}))	
 }
 
 fn handle_outer<'a,'b>(outer: OuterExp<'a,'b>) -> GrammerNode<'a,'b> {
 	match outer.keyword.clone() {
-		KeyWord::Import(_) => todo!(),
+		KeyWord::Import(_) => parse_import(outer.keyword,outer.body),
 		KeyWord::FuncDec(_) => {
 
 			let (input,name,error) = match outer.body.take_err(1usize) {
@@ -144,84 +186,508 @@ fn handle_outer<'a,'b>(outer: OuterExp<'a,'b>) -> GrammerNode<'a,'b> {
 					);
 				}
 			};
+			let error = error.map(Box::new);
+
+			let last_span = name.unwrap_or(outer.keyword.get_span());
+			let (input,clause,block_error) = parse_clause(input,outer.keyword,name,last_span);
+
+			let extra_error = match input.input_len() {
+				0 => None,
+				_ => Some(Box::new(unexpected_tokens(&input)))
+			};
+
+			let error = combine_errors(error,block_error);
+			let error = combine_errors(error,extra_error);
+
+			let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Function(FuncDef{
+				name,
+				clauses: vec![clause],
+			}).into();
+			node.error = error;
+			node
+		}
+		//parse_outer_keyword only gives us the ones above
+		keyword => {
+			let error = unexpected_tokens(&outer.body);
+			let node :GrammerNode<'a,'b> = GrammerNodeBase::KeyWord(keyword).into();
+			node.with_error(error)
+		}
+	}
+}
+
+//the (params) of a def or a lamda. made of patterns so clauses can dispatch on their arguments
+pub fn parse_params<'a,'b>(input:TokenSlice<'a,'b>,last_span:LocatedSpan<&'a str>) -> (TokenSlice<'a,'b>,PatternList<'a>,Option<Box<UserSideError<'a>>>) {
+	let (input,head,head_error) = parse_assumed_paren(input,'(',last_span);
+	let (items,params_error) = parse_pattern_list(paren_inner(head.body));
+	let params = PatternList{start: head.start,items,end: head.end};
+	(input,params,combine_errors(head_error,params_error))
+}
+
+//where a def guard without () ends
+fn stop_guard(token:&LexToken) -> bool {
+	is_delimiter(token,'{') || matches!(token.tag,LexTag::Ender(_))
+}
+
+//(params) if (guard) {body} of a def
+fn parse_clause<'a,'b>(input:TokenSlice<'a,'b>,keyword:KeyWord<'a>,name:Option<LocatedSpan<&'a str>>,last_span:LocatedSpan<&'a str>) -> (TokenSlice<'a,'b>,FuncClause<'a,'b>,Option<Box<UserSideError<'a>>>) {
+	let (input,params,mut error) = parse_params(input,last_span);
+	let last_span = params.end.or(params.start).unwrap_or(last_span);
 
+	let (input,guard,last_span) = match peek(&input) {
+		//if x > 0 { is what match guards look like. we still take the condition so only the missing () is reported
+		Some(token) if is_guard(token) && input.input_len() > 1 && !is_delimiter(&input[1],'(') && !stop_guard(&input[1]) => {
+			let (input,tokens) = skip_till(input.take_split(1).0,stop_guard);
+			let last = TokenSlice::last(&tokens).unwrap().span;
+			error = combine_errors(error,Some(Box::new(UserSideError::UnparenthesizedGuard(tokens[0].span,last))));
 
+			let condition = Some(Box::new(parse_group(tokens)));
+			(input,Some(Guard{keyword: KeyWord::If(token.span),condition}),last)
+		}
+		Some(token) if is_guard(token) => {
+			let (input,condition,condition_error) = parse_assumed_paren(input.take_split(1).0,'(',token.span);
+			error = combine_errors(error,condition_error);
+			let last_span = condition.end.or(condition.start).unwrap_or(token.span);
 
-			// let ans = FuncDef{
-			// 	keyword:outer.keyword,
-			// 	name: name,
-			// };
-			todo!()
-			// GrammerNodeBase::Function(FuncDef{
-			// 	word:keyword,
-			// 	name:opt(parse_word)
-			// })
+			let condition = process_paren(condition,parse_group).body;
+			if condition.is_none() {
+				error = combine_errors(error,Some(Box::new(UserSideError::MissingGuardCondition(token.span))));
+			}
+			(input,Some(Guard{keyword: KeyWord::If(token.span),condition}),last_span)
 		}
-		_ => unreachable!()
+		_ => (input,None,last_span),
+	};
+
+	let (input,body,body_error) = parse_assumed_paren(input,'{',last_span);
+	let clause = FuncClause{
+		keyword,
+		name,
+		params,
+		guard,
+		body: process_paren(body,parse_body),
+	};
+	(input,clause,combine_errors(error,body_error))
+}
+
+//clauses of the same function are merged into the first one.
+//they have to come one after the other and take the same number of arguments
+pub fn merge_clauses<'a,'b>(nodes:Vec<GrammerNode<'a,'b>>) -> Vec<GrammerNode<'a,'b>> {
+	let mut merged :Vec<GrammerNode<'a,'b>> = Vec::new();
+	for mut node in nodes {
+		let name = match function_name(&node) {
+			Some(name) => name,
+			None => {
+				merged.push(node);
+				continue;
+			}
+		};
+
+		let last = match merged.last_mut() {
+			Some(last) if function_name(last).is_some_and(|x| x.fragment() == name.fragment()) => last,
+			_ => {
+				let first = merged.iter().filter_map(function_name).find(|x| x.fragment() == name.fragment());
+				if let Some(first) = first {
+					node.error = combine_errors(node.error,Some(Box::new(UserSideError::SeparatedClause(name,first))));
+				}
+				merged.push(node);
+				continue;
+			}
+		};
+
+		let (GrammerNodeBase::Function(def),GrammerNodeBase::Function(clauses)) = (&mut last.base,node.base) else {
+			unreachable!("function_name only matches functions")
+		};
+		last.error = combine_errors(last.error.take(),node.error);
+
+		let first = &def.clauses[0];
+		let (first_name,expected,first_closed) = (first.name.unwrap_or(name),first.params.items.len(),first.params.end.is_some());
+		for clause in clauses.clauses {
+			//a broken head has no meaningful arity
+			if first_closed && clause.params.end.is_some() && clause.params.items.len() != expected {
+				let error = UserSideError::ClauseArity(clause.name.unwrap_or(name),first_name,expected,clause.params.items.len());
+				last.error = combine_errors(last.error.take(),Some(Box::new(error)));
+			}
+			def.clauses.push(clause);
+		}
+	}
+	merged
+}
+
+fn function_name<'a>(node:&GrammerNode<'a,'_>) -> Option<LocatedSpan<&'a str>> {
+	match &node.base {
+		GrammerNodeBase::Function(def) => def.name,
+		_ => None,
 	}
 }
 
-fn parse_assumed_paren<'a,'b>(input:TokenSlice<'a,'b>) -> GResult<'a,'b> {
+//(start) {body} of an if. start_parser handles whatever goes in the (). defs and lamdas go through parse_params
+pub fn parse_block<'a,'b,F>(input:TokenSlice<'a,'b>,last_span:LocatedSpan<&'a str>,start_parser:F) -> (TokenSlice<'a,'b>,Block<'a,'b>,Option<Box<UserSideError<'a>>>) 
+where F: FnOnce(TokenSlice<'a,'b>) -> GrammerNode<'a,'b>
+{
+	let (input,start,start_error) = parse_assumed_paren(input,'(',last_span);
 	
-	let (input,(extra,del)) = tuple((
-		// take_while(is_comment),
-		take_till(is_paren),
-		// take_while(is_comment),
-		opt(take(1usize))
-		)
-	)(input)?;
-	let error = match extra.input_len(){
+	let last_span = start.end.or(start.start).unwrap_or(last_span);
+	let (input,body,body_error) = parse_assumed_paren(input,'{',last_span);
+
+	let block = Block{
+		start: Box::new(process_paren(start,start_parser)),
+		body: Box::new(process_paren(body,parse_body)),
+	};
+	(input,block,combine_errors(start_error,body_error))
+}
+
+//the inside of a {} body. statements are split by ; and the last one is an implicit return
+pub fn parse_body<'a,'b>(input:TokenSlice<'a,'b>) -> GrammerNode<'a,'b> {
+	let mut input = input;
+	let mut statements = Vec::new();
+
+	while input.input_len() > 0 {
+		if peek(&input).is_some_and(|t| is_ender(t,';')) {
+			input = input.take_split(1).0;
+			continue;
+		}
+
+		let (remaining,statement) = parse_statement(input);
+		input = remaining;
+		statements.push(statement);
+	}
+
+	statements.into()
+}
+
+fn parse_statement<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	if is_assign(&input) {
+		return parse_assign(input);
+	}
+
+	let (input,word) = match peek(&input).and_then(|t| match_keyword(t.span).filter(|_| t.tag == LexTag::Word())) {
+		Some(word @ KeyWord::Return(_)) => (input.take_split(1).0,Some(word)),
+		_ => (input,None),
+	};
+
+	let (input,expr) = parse_expr(input);
+	let (input,junk,ender) = finish_statement(input);
+	let junk_error = match junk.input_len() {
 		0 => None,
-		_ => Some(UserSideError::UnexpectedTokens(extra.spans()))
+		_ => Some(Box::new(unexpected_tokens(&junk))),
+	};
+
+	let (value,error) = match expr {
+		Some(expr) => expr.into_value(),
+		None => {
+			if word.is_none() {
+				let error = unexpected_tokens(&junk);
+				let node :GrammerNode<'a,'b> = GrammerNodeBase::Unprocessed(junk).into();
+				return (input,node.with_error(error));
+			}
+			(None,None)
+		}
+	};
+	let error = combine_errors(error,junk_error);
+
+	//only the last statement returns implicitly
+	let base = match value {
+		Some(value) if word.is_none() && input.input_len() > 0 => GrammerNodeBase::Val(value),
+		value => GrammerNodeBase::Return(Return{word,value,ender}),
+	};
+
+	let mut node :GrammerNode<'a,'b> = base.into();
+	node.error = error;
+	(input,node)
+}
+
+//name = ...
+fn is_assign(input:&TokenSlice) -> bool {
+	input.input_len() >= 2 
+	&& input[0].tag == LexTag::Word() 
+	&& match_keyword(input[0].span).is_none()
+	&& is_op(&input[1],BinaryOp::OneEqul)
+}
+
+//assumes is_assign
+fn parse_assign<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,GrammerNode<'a,'b>) {
+	let left = Varible{name:input[0].span,count:0};
+	let operator = input[1].span;
+
+	let (input,expr) = parse_expr(input.take_split(2).0);
+	let (input,junk,ender) = finish_statement(input);
+
+	let (right,error) = match expr {
+		Some(expr) => expr.into_value(),
+		None => (None,Some(Box::new(UserSideError::MissingRightOperand(operator)))),
+	};
+	let error = match junk.input_len() {
+		0 => error,
+		_ => combine_errors(error,Some(Box::new(unexpected_tokens(&junk)))),
 	};
-	if del.is_none(){
-		todo!();
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Declare(Assign{
+		left,
+		operator,
+		right: right.map(Box::new),
+		ender: ender.map(Box::new),
+	}).into();
+	node.error = error;
+	(input,node)
+}
+
+//skips whatever is left of a statement. returns the remaining input, the skipped junk and the ; if there was one
+fn finish_statement<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,TokenSlice<'a,'b>,Option<LocatedSpan<&'a str>>) {
+	let (input,junk) = skip_to_ender(input,';');
+	match peek(&input) {
+		Some(token) => (input.take_split(1).0,junk,Some(token.span)),
+		None => (input,junk,None),
+	}
+}
+
+fn parse_import<'a,'b>(keyword:KeyWord<'a>,input:TokenSlice<'a,'b>) -> GrammerNode<'a,'b> {
+	if input.input_len() == 0 {
+		let node :GrammerNode<'a,'b> = GrammerNodeBase::KeyWord(keyword.clone()).into();
+		return node.with_error(UserSideError::EmptyImport(keyword.get_span()));
+	}
+
+	let mut input = input;
+	let mut error = None;
+	let mut last_span = keyword.get_span();
+
+	let mut path = Vec::new();
+	let mut items = None;
+	let mut items_start = None;
+	loop {
+		match peek(&input) {
+			Some(token) if token.tag == LexTag::Word() => {
+				if match_keyword(token.span).is_some() {
+					error = combine_errors(error,Some(Box::new(UserSideError::ReservedName(token.span))));
+				}
+				path.push(token.span);
+				input = input.take_split(1).0;
+			}
+			_ => {
+				error = combine_errors(error,Some(Box::new(UserSideError::MissingImportSegment(last_span))));
+				break;
+			}
+		}
+
+		match peek(&input) {
+			Some(token) if is_op(token,BinaryOp::DoubleDots) => {
+				last_span = token.span;
+				input = input.take_split(1).0;
+			}
+			_ => break,
+		}
+
+		if peek(&input).is_some_and(|t| is_delimiter(t,'{')) {
+			items_start = Some(input[0].span);
+			let (remaining,selected,items_error) = parse_import_items(input);
+			input = remaining;
+			items = Some(selected);
+			error = combine_errors(error,items_error);
+			break;
+		}
+	}
+
+	let mut alias = None;
+	let mut alias_keyword = None;
+	if let Some(token) = peek(&input) {
+		if token.tag == LexTag::Word() && *token.span.fragment() == "as" {
+			alias_keyword = Some(token.span);
+			input = input.take_split(1).0;
+			match peek(&input) {
+				Some(name) if name.tag == LexTag::Word() && match_keyword(name.span).is_none() => {
+					alias = Some(name.span);
+					input = input.take_split(1).0;
+				}
+				//a::{f, g} as is reported below for the as itself
+				_ if items_start.is_some() => {}
+				_ => {
+					error = combine_errors(error,Some(Box::new(UserSideError::MissingImportAlias(token.span))));
+				}
+			}
+		}
+	}
+
+	//there is nothing to alias in a::{f, g}. the name is still read so it does not turn into junk
+	if let (Some(start),Some(as_span)) = (items_start,alias_keyword) {
+		error = combine_errors(error,Some(Box::new(UserSideError::AliasedImportItems(as_span,start))));
+	}
+
+	if peek(&input).is_some_and(|t| is_ender(t,';')) {
+		input = input.take_split(1).0;
+	}
+
+	if input.input_len() > 0 {
+		error = combine_errors(error,Some(Box::new(unexpected_tokens(&input))));
 	}
-	let del = del.unwrap();
-
-	let start = match del[0].tag {
-		LexTag::Delimiter(c) => {
-			if is_opener(c) {
-				del[0].span
-			} else {
-				let mut ans : GrammerNode<'a,'b>= GrammerNodeBase::Paren(ParenExpr{
-					start: None,
-					body: None, 
-					end: Some(del[0].span)
-				}).into();
-
-				ans.error=error.map(|e| Box::new(e));
-				return Ok((input,ans));
+
+	let mut node :GrammerNode<'a,'b> = GrammerNodeBase::Import(Import{
+		keyword,
+		path,
+		alias,
+		items,
+	}).into();
+	node.error = error;
+	node
+}
+
+//the {f, g} part of an import. assumes the input starts with {
+fn parse_import_items<'a,'b>(input:TokenSlice<'a,'b>) -> (TokenSlice<'a,'b>,Vec<LocatedSpan<&'a str>>,Option<Box<UserSideError<'a>>>) {
+	let start = input[0].span;
+	let mut input = input.take_split(1).0;
+	let mut error = None;
+	let mut items = Vec::new();
+
+	while let Some(token) = peek(&input) {
+		input = input.take_split(1).0;
+		match token.tag {
+			LexTag::Delimiter('}') => {
+				if items.is_empty() {
+					error = combine_errors(error,Some(Box::new(UserSideError::InvalidImportItem(token.span))));
+				}
+				return (input,items,error);
+			}
+			LexTag::Ender(',') => {},
+			LexTag::Word() if match_keyword(token.span).is_none() => items.push(token.span),
+			_ => {
+				error = combine_errors(error,Some(Box::new(UserSideError::InvalidImportItem(token.span))));
 			}
-		},
-		_ => unreachable!(),
+		}
+	}
+
+	let last = items.last().copied().unwrap_or(start);
+	(input,items,combine_errors(error,Some(Box::new(UserSideError::UnclosedPar(start,last)))))
+}
+
+//finds the closer matching the opener at the start of the input
+
+//assumes the next delimiter is the opener we are after. we never look past an ender.
+//tokens skipped on the way are reported and an unclosed paren still keeps the rest of the input as its body.
+//last_span is used for reporting when there is nothing to point at
+pub fn parse_assumed_paren<'a,'b>(input:TokenSlice<'a,'b>,opener:char,last_span:LocatedSpan<&'a str>) -> ParenResult<'a,'b> {
+	let split = input.position(|t| is_paren(t) || matches!(t.tag,LexTag::Ender(_))).unwrap_or(input.input_len());
+	let (input,extra) = input.take_split(split);
+
+	let error = match extra.input_len(){
+		0 => None,
+		_ => Some(Box::new(unexpected_tokens(&extra)))
 	};
-	// let ans = GrammerNodeBase::Par(ParenExpr{
-	// })
-	todo!()
-}
-// enum AssumeWord<'a> {
-// 	// WrongTag(LexToken<'a>),
-// 	KeyWord(KeyWord<'a>),
-// 	Name(LexToken<'a>),
-// }
+	let last_span = TokenSlice::last(&extra).map(|t| t.span).unwrap_or(last_span);
 
-// fn parse_word<'a,'b>(input:TokenSlice<'a,'b>) -> TResult<'a,'b,AssumeWord<'a>> {
-// 	let (input,slice) = take(1usize)(input)?;
-// 	let x = slice[0].clone();
-// 	let ans = match x.tag {
-// 		LexTag::Word() => match match_keyword(x.span) {
-// 			None => AssumeWord::Name(x),
-// 			Some(kw) => AssumeWord::KeyWord(kw),
+	let empty = ParenExpr{
+		start: None,
+		body: None,
+		end: None,
+	};
 
-// 		},
-// 		// _ => AssumeWord::WrongTag(x)
-// 		_ => {return Err(Error(()));}
-// 	};
-// 	Ok((input,ans))
-// }
+	let c = match peek(&input).map(|t| &t.tag) {
+		Some(LexTag::Delimiter(c)) => *c,
+		_ => {
+			let error = combine_errors(error,Some(Box::new(UserSideError::ExpectedParen(last_span,opener))));
+			return (input,empty,error);
+		}
+	};
+	
+	if !is_opener(c) {
+		let end = input[0].span;
+		let error = combine_errors(error,Some(Box::new(UserSideError::ExtraPar(end))));
+		return (input.take_split(1).0,ParenExpr{end:Some(end),..empty},error);
+	}
+
+	if c != opener {
+		//leave it for whoever wants that paren
+		let error = combine_errors(error,Some(Box::new(UserSideError::ExpectedParen(last_span,opener))));
+		return (input,empty,error);
+	}
+
+	let (input,paren,paren_error) = take_paren(input);
+	(input,paren,combine_errors(error,paren_error))
+}
+
+//splits off the paren group at the start of the input. the inside is left unprocessed.
+//callers should make sure there is an opener there, anything else is eaten as a single bad token
+pub fn take_paren<'a,'b>(input:TokenSlice<'a,'b>) -> ParenResult<'a,'b> {
+	let empty = ParenExpr{start:None,body:None,end:None};
+	let mut trees = token_trees(input.clone());
+	let group = match trees.next() {
+		Some(TokenTree::Group(group)) => group,
+		Some(TokenTree::Leaf(token)) => {
+			let error = match token.tag {
+				LexTag::Delimiter(_) => UserSideError::ExtraPar(token.span),
+				_ => unexpected_tokens(&input.slice(..1)),
+			};
+			return (trees.rest(),empty,Some(Box::new(error)));
+		}
+		None => return (input,empty,None),
+	};
+
+	let start = group.open.span;
+	let (end,error) = match group.close {
+		//closers made up by the delimiter pass carry their own error
+		Some(close) => (Some(close.span),match (&group.open.tag,&close.tag) {
+			(LexTag::Delimiter(opener),LexTag::Delimiter(closer)) if *closer == get_closer(*opener) => close.error.clone(),
+			_ => Some(Box::new(UserSideError::UnclosedPar(start,close.span))),
+		}),
+		None => {
+			let last = TokenSlice::last(&group.body).map(|t| t.span).unwrap_or(start);
+			(None,Some(Box::new(UserSideError::UnclosedPar(start,last))))
+		}
+	};
+
+	let body = match group.body.input_len() {
+		0 => None,
+		_ => Some(Box::new(GrammerNodeBase::Unprocessed(group.body).into())),
+	};
+
+	(trees.rest(),ParenExpr{start:Some(start),body,end},error)
+}
+
+//the raw tokens inside a paren from parse_assumed_paren or take_paren
+pub fn paren_inner<'a,'b>(body:Option<Box<GrammerNode<'a,'b>>>) -> TokenSlice<'a,'b> {
+	match body.map(|b| b.base) {
+		Some(GrammerNodeBase::Unprocessed(inner)) => inner,
+		_ => TokenSlice::new(&[]),
+	}
+}
 
-fn match_keyword<'a>(x:LocatedSpan<&'a str>) -> Option<KeyWord<'a>> {
+//runs a parser over the unprocessed inside of a paren
+pub fn process_paren<'a,'b,F>(paren:ParenExpr<'a,'b>,f:F) -> ParenExpr<'a,'b> 
+where F: FnOnce(TokenSlice<'a,'b>) -> GrammerNode<'a,'b>
+{
+	let body = paren.body.map(|body| match body.base {
+		GrammerNodeBase::Unprocessed(inner) => {
+			let mut node = f(inner);
+			node.error = combine_errors(body.error,node.error);
+			Box::new(node)
+		}
+		_ => body,
+	});
+	ParenExpr{body,..paren}
+}
+
+//skips to the next ender (or the end of the input) stepping over whole token trees.
+//returns the remaining input and what was skipped. the ender itself is not consumed
+pub fn skip_to_ender<'a,'b>(input:TokenSlice<'a,'b>,ender:char) -> (TokenSlice<'a,'b>,TokenSlice<'a,'b>) {
+	skip_till(input,|t| is_ender(t,ender))
+}
+
+//same as skip_to_ender for any stopping token
+pub fn skip_till<'a,'b,F>(input:TokenSlice<'a,'b>,stop:F) -> (TokenSlice<'a,'b>,TokenSlice<'a,'b>)
+where F: Fn(&LexToken) -> bool
+{
+	let mut len = 0;
+	for tree in token_trees(input.clone()) {
+		//a group can only be stopped at by its opener
+		let first = match &tree {
+			TokenTree::Leaf(token) => *token,
+			TokenTree::Group(group) => group.open,
+		};
+		if stop(first) {
+			break;
+		}
+		len += tree.len();
+	}
+	input.take_split(len)
+}
+
+pub fn match_keyword<'a>(x:LocatedSpan<&'a str>) -> Option<KeyWord<'a>> {
 	match *x.fragment() {
 		"null" | "nil" => Some(KeyWord::Nil(x)),
 	
@@ -236,6 +702,7 @@ fn match_keyword<'a>(x:LocatedSpan<&'a str>) -> Option<KeyWord<'a>> {
 		
 		"cond" => Some(KeyWord::Cond(x)),
 		"match" => Some(KeyWord::Match(x)),
+		"for" => Some(KeyWord::For(x)),
 		_ => None,
 	}
 }
@@ -275,6 +742,8 @@ fn match_keyword<'a>(x:LocatedSpan<&'a str>) -> Option<KeyWord<'a>> {
 // }
 #[cfg(test)]
 use crate::lex_full_text;
+#[cfg(test)]
+use crate::tree::balance;
 
 #[test]
 #[no_mangle]
@@ -293,7 +762,7 @@ fn test_parse_outer() {
 
     "#;
 
-    let lexed = lex_full_text(input_str);
+    let lexed = balance(lex_full_text(input_str));
     let input = TokenSlice::new(&lexed);
 
     let(input,first) = parse_outer_scope(input).unwrap();
@@ -313,4 +782,245 @@ fn test_parse_outer() {
     assert!(second.body.input_len()==0);
     assert!(fith.body.input_len()==1);
 
-}	
\ No newline at end of file
+}	
+#[test]
+#[no_mangle]
+fn test_parse_func_defs() {
+    let input_str = r#"
+        def add(a, b) { a + b }
+        def (x) { x }
+        def if () {}
+        def broken(x { x
+        def after() {}
+        def
+    "#;
+
+    let lexed = balance(lex_full_text(input_str));
+    let input = TokenSlice::new(&lexed);
+
+    let (input,node) = parse(input).unwrap();
+    assert!(input.input_len()==0);
+
+    let nodes = match node.base {
+    	GrammerNodeBase::Sequence(nodes) => nodes,
+    	_ => unreachable!("expected a sequence"),
+    };
+    assert!(nodes.len()==6);
+
+    let get_def = |i:usize| match &nodes[i].base {
+    	GrammerNodeBase::Function(def) => def,
+    	_ => unreachable!("expected a function"),
+    };
+
+    let add = get_def(0);
+    assert!(nodes[0].error.is_none());
+    assert_eq!(add.name.map(|n| *n.fragment()),Some("add"));
+    assert!(add.clauses[0].params.end.is_some());
+    assert!(matches!(add.clauses[0].body.body.as_deref().map(|b| &b.base),Some(GrammerNodeBase::Sequence(_))));
+
+    assert!(get_def(1).name.is_none());
+    assert!(matches!(nodes[1].error.as_deref(),Some(UserSideError::MissingFuncName(_))));
+    assert!(matches!(nodes[2].error.as_deref(),Some(UserSideError::ReservedName(_))));
+
+    let broken = get_def(3);
+    assert!(nodes[3].error.is_some());
+    //the delimiter pass closes the ( for us once the next def shows up
+    assert!(broken.clauses[0].params.end.is_some_and(|end| end.fragment().is_empty()));
+    assert!(broken.clauses[0].body.start.is_none());
+
+    let after = get_def(4);
+    assert!(nodes[4].error.is_none());
+    assert!(after.clauses[0].params.items.is_empty());
+    assert!(after.clauses[0].body.end.is_some());
+
+    assert!(matches!(nodes[5].error.as_deref(),Some(UserSideError::EmptyFuncDef(_))));
+}
+
+#[test]
+#[no_mangle]
+fn test_parse_imports() {
+    let input_str = r#"
+        import a::b::c
+        import a::b as x;
+        import a::b::{f, g}
+        import a::
+        import a as
+        import a::{f, 3}
+        import
+        import a::b::{f, g} as x
+    "#;
+
+    let lexed = lex_full_text(input_str);
+    let input = TokenSlice::new(&lexed);
+
+    let (_,node) = parse(input).unwrap();
+    let nodes = match node.base {
+    	GrammerNodeBase::Sequence(nodes) => nodes,
+    	_ => unreachable!("expected a sequence"),
+    };
+    assert!(nodes.len()==8);
+
+    let get_import = |i:usize| match &nodes[i].base {
+    	GrammerNodeBase::Import(import) => import,
+    	_ => unreachable!("expected an import"),
+    };
+    fn names<'a>(spans:&[LocatedSpan<&'a str>]) -> Vec<&'a str> {
+    	spans.iter().map(|s| *s.fragment()).collect()
+    }
+
+    let first = get_import(0);
+    assert!(nodes[0].error.is_none());
+    assert_eq!(names(&first.path),vec!["a","b","c"]);
+    assert!(first.alias.is_none() && first.items.is_none());
+
+    let second = get_import(1);
+    assert!(nodes[1].error.is_none());
+    assert_eq!(names(&second.path),vec!["a","b"]);
+    assert_eq!(second.alias.map(|a| *a.fragment()),Some("x"));
+
+    let third = get_import(2);
+    assert!(nodes[2].error.is_none());
+    assert_eq!(names(&third.path),vec!["a","b"]);
+    assert_eq!(third.items.as_deref().map(names),Some(vec!["f","g"]));
+
+    assert!(matches!(nodes[3].error.as_deref(),Some(UserSideError::MissingImportSegment(_))));
+    assert!(matches!(nodes[4].error.as_deref(),Some(UserSideError::MissingImportAlias(_))));
+    assert!(matches!(nodes[5].error.as_deref(),Some(UserSideError::InvalidImportItem(_))));
+    assert_eq!(get_import(5).items.as_deref().map(names),Some(vec!["f"]));
+    assert!(matches!(nodes[6].error.as_deref(),Some(UserSideError::EmptyImport(_))));
+    assert!(matches!(nodes[7].error.as_deref(),Some(UserSideError::AliasedImportItems(as_span,start)) if *as_span.fragment()=="as" && *start.fragment()=="{"));
+}
+
+#[test]
+#[no_mangle]
+fn test_parse_body() {
+    let input_str = "f(x); return x + 1; y * 2 ) z; (a +) b";
+    let lexed = lex_full_text(input_str);
+    let node = parse_body(TokenSlice::new(&lexed));
+
+    let nodes = match node.base {
+    	GrammerNodeBase::Sequence(nodes) => nodes,
+    	_ => unreachable!("expected a sequence"),
+    };
+    assert!(nodes.len()==4);
+
+    assert!(matches!(nodes[0].base,GrammerNodeBase::Val(_)));
+    assert!(nodes[0].error.is_none());
+
+    match &nodes[1].base {
+    	GrammerNodeBase::Return(ret) => {
+    		assert!(ret.word.is_some() && ret.value.is_some() && ret.ender.is_some());
+    	}
+    	_ => unreachable!("expected a return"),
+    }
+    assert!(nodes[1].error.is_none());
+
+    assert!(matches!(nodes[2].error.as_deref(),Some(UserSideError::UnexpectedTokens(_))));
+
+    //the last statement is an implicit return
+    match &nodes[3].base {
+    	GrammerNodeBase::Return(ret) => {
+    		assert!(ret.word.is_none() && ret.value.is_some() && ret.ender.is_none());
+    	}
+    	_ => unreachable!("expected a return"),
+    }
+    assert!(nodes[3].error.is_some());
+}
+
+#[test]
+#[no_mangle]
+fn test_parse_assign() {
+    let input_str = "x = 1 + 2; y = ; x";
+    let lexed = lex_full_text(input_str);
+    let node = parse_body(TokenSlice::new(&lexed));
+
+    let nodes = match node.base {
+    	GrammerNodeBase::Sequence(nodes) => nodes,
+    	_ => unreachable!("expected a sequence"),
+    };
+    assert!(nodes.len()==3);
+
+    match &nodes[0].base {
+    	GrammerNodeBase::Declare(assign) => {
+    		assert_eq!(*assign.left.name.fragment(),"x");
+    		assert!(assign.right.is_some() && assign.ender.is_some());
+    	}
+    	_ => unreachable!("expected an assignment"),
+    }
+    assert!(nodes[0].error.is_none());
+
+    match &nodes[1].base {
+    	GrammerNodeBase::Declare(assign) => assert!(assign.right.is_none()),
+    	_ => unreachable!("expected an assignment"),
+    }
+    assert!(matches!(nodes[1].error.as_deref(),Some(UserSideError::MissingRightOperand(span)) if *span.fragment()=="="));
+}
+
+#[test]
+#[no_mangle]
+fn test_split_outer() {
+    let lexed = balance(lex_full_text("x def f() { def } import a def g(y) {}"));
+    let pieces = split_outer(&TokenSlice::new(&lexed));
+    let firsts = pieces.iter().map(|x| *x[0].span.fragment()).collect::<Vec<_>>();
+    assert_eq!(firsts,vec!["x","def","import","def"]);
+    assert_eq!(pieces.iter().map(|x| x.input_len()).sum::<usize>(),lexed.len());
+    assert_eq!(pieces[1].input_len(),7);
+}
+
+#[test]
+#[no_mangle]
+fn test_parse_clauses() {
+    let input_str = r#"
+        def fact(0) { 1 }
+        def fact(n) if (n > 0) { n * fact(n - 1) }
+        def other() {}
+        def fact(n, m) { n }
+        def pair(a, b) { a }
+        def pair(a) if () { a }
+    "#;
+
+    let lexed = balance(lex_full_text(input_str));
+    let (_,node) = parse(TokenSlice::new(&lexed)).unwrap();
+    let nodes = match node.base {
+    	GrammerNodeBase::Sequence(nodes) => nodes,
+    	_ => unreachable!("expected a sequence"),
+    };
+    assert!(nodes.len()==4);
+
+    let fact = match &nodes[0].base {
+    	GrammerNodeBase::Function(def) => def,
+    	_ => unreachable!("expected a function"),
+    };
+    assert!(nodes[0].error.is_none());
+    assert!(fact.clauses.len()==2);
+    assert!(fact.clauses[0].guard.is_none());
+    assert!(fact.clauses[1].guard.as_ref().is_some_and(|x| x.condition.is_some()));
+
+    //a clause after a different function stays apart
+    assert!(matches!(nodes[2].error.as_deref(),
+    	Some(UserSideError::SeparatedClause(span,first)) if span.location_line()==5 && first.location_line()==2
+    ));
+
+    let errors = match nodes[3].error.as_deref() {
+    	Some(UserSideError::Compound(errors)) => errors,
+    	other => unreachable!("expected 2 errors got {:?}",other),
+    };
+    assert!(errors.iter().any(|x| matches!(x,UserSideError::MissingGuardCondition(_))));
+    assert!(errors.iter().any(|x| matches!(x,UserSideError::ClauseArity(_,_,2,1))));
+
+    let first_error = |text| {
+    	let lexed = balance(lex_full_text(text));
+    	let (_,node) = parse(TokenSlice::new(&lexed)).unwrap();
+    	match node.base {
+    		GrammerNodeBase::Sequence(nodes) => nodes[0].error.clone(),
+    		_ => unreachable!("expected a sequence"),
+    	}
+    };
+    assert!(matches!(first_error("def f(a,, b) {a}").as_deref(),Some(UserSideError::DuplicateComma(span)) if span.location_offset()==8));
+    assert!(matches!(first_error("def f(a, b,) {a}").as_deref(),Some(UserSideError::TrailingComma(span)) if span.location_offset()==10));
+
+    //a match style guard gets one error and the clause is still parsed
+    assert!(matches!(first_error("def f(x) if x > 0 { x }").as_deref(),
+    	Some(UserSideError::UnparenthesizedGuard(start,end)) if *start.fragment()=="x" && *end.fragment()=="0"
+    ));
+}
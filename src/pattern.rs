@@ -0,0 +1,181 @@
+use crate::token::{TokenSlice,LexTag,BinaryOp};
+use crate::ast::{Pattern,PatternList,Varible,KeyWord,SmallLexToken};
+use crate::errors::{UserSideError,combine_errors};
+use crate::parse::{peek,match_keyword,take_paren,skip_to_ender,paren_inner,unexpected_tokens};
+
+use nom::{InputLength,InputTake};
+
+/*
+patterns are what goes on the left of a match arm.
+
+ 1 -2.5 "str" %ok :ok nil   literals, matched by value
+ _                          wildcard, matches anything and binds nothing
+ x                          binds whatever is there to x
+ [a, b]                     an array of exactly that length
+ {a, b}                     a tuple of exactly that length
+
+patterns nest so {%ok, [x, _]} is fine.
+*/
+
+pub type PatternResult<'a,'b> = (TokenSlice<'a,'b>,Option<Pattern<'a>>,Option<Box<UserSideError<'a>>>);
+
+//parses a single pattern from the start of the input.
+//returns None without consuming anything if the input does not start with a pattern
+pub fn parse_pattern<'a,'b>(input:TokenSlice<'a,'b>) -> PatternResult<'a,'b> {
+	let token = match peek(&input) {
+		Some(token) => token,
+		None => return (input,None,None),
+	};
+
+	match &token.tag {
+		LexTag::Int(_) | LexTag::Float(_) | LexTag::Atom()
+		| LexTag::String(..) | LexTag::RawString(..) | LexTag::PoisonString(_) => {
+			(input.take_split(1).0,Some(Pattern::Literal(token.clone().into())),token.error.clone())
+		}
+
+		//-1 is two tokens. the sign is folded into the value and the span stays on the number
+		LexTag::Op(op @ (BinaryOp::Sub | BinaryOp::Add)) if input.input_len() > 1 && matches!(input[1].tag,LexTag::Int(_) | LexTag::Float(_)) => {
+			let number = &input[1];
+			let tag = match (op,&number.tag) {
+				(BinaryOp::Sub,LexTag::Int(x)) => LexTag::Int(x.wrapping_neg()),
+				(BinaryOp::Sub,LexTag::Float(x)) => LexTag::Float(-x),
+				(_,tag) => tag.clone(),
+			};
+			let literal = SmallLexToken{span: number.span,tag};
+			(input.take_split(2).0,Some(Pattern::Literal(literal)),number.error.clone())
+		}
+
+		LexTag::Word() => match match_keyword(token.span) {
+			None if *token.span.fragment() == "_" => (input.take_split(1).0,Some(Pattern::Wildcard(token.span)),None),
+			None => (input.take_split(1).0,Some(Pattern::Bind(Varible{name:token.span,count:0})),None),
+			Some(KeyWord::Nil(span)) => (input.take_split(1).0,Some(Pattern::Nil(KeyWord::Nil(span))),None),
+			Some(_) => (input,None,None),
+		},
+
+		LexTag::Delimiter(c @ ('[' | '{')) => {
+			let c = *c;
+			let (input,paren,error) = take_paren(input);
+			let (items,items_error) = parse_pattern_list(paren_inner(paren.body));
+
+			let list = PatternList{
+				start: paren.start,
+				items,
+				end: paren.end,
+			};
+			let pattern = match c {
+				'[' => Pattern::Array(list),
+				_ => Pattern::Tuple(list),
+			};
+			(input,Some(pattern),combine_errors(error,items_error))
+		}
+
+		_ => (input,None,None),
+	}
+}
+
+//a, b, c as patterns. anything that is not a pattern becomes Pattern::Invalid
+pub fn parse_pattern_list<'a,'b>(input:TokenSlice<'a,'b>) -> (Vec<Pattern<'a>>,Option<Box<UserSideError<'a>>>) {
+	let mut input = input;
+	let mut items = Vec::new();
+	let mut error = None;
+
+	while input.input_len() > 0 {
+		let (remaining,item) = skip_to_ender(input,',');
+		let ender = peek(&remaining).map(|token| token.span);
+		input = match remaining.input_len() {
+			0 => remaining,
+			_ => remaining.take_split(1).0,
+		};
+
+		//same comma errors as parse_list_items
+		if item.input_len() == 0 {
+			error = combine_errors(error,ender.map(|x| Box::new(UserSideError::DuplicateComma(x))));
+			continue;
+		}
+		if input.input_len() == 0 {
+			error = combine_errors(error,ender.map(|x| Box::new(UserSideError::TrailingComma(x))));
+		}
+
+		let (pattern,item_error) = parse_full_pattern(item);
+		error = combine_errors(error,item_error);
+		items.push(pattern);
+	}
+
+	(items,error)
+}
+
+//parses a pattern that should take up the whole input
+pub fn parse_full_pattern<'a,'b>(input:TokenSlice<'a,'b>) -> (Pattern<'a>,Option<Box<UserSideError<'a>>>) {
+	let first = input[0].span;
+	let (rest,pattern,error) = parse_pattern(input);
+	match pattern {
+		Some(pattern) => {
+			let error = match rest.input_len() {
+				0 => error,
+				_ => combine_errors(error,Some(Box::new(unexpected_tokens(&rest)))),
+			};
+			(pattern,error)
+		}
+		None => (Pattern::Invalid(first),Some(Box::new(UserSideError::InvalidPattern(first)))),
+	}
+}
+
+#[cfg(test)]
+use crate::lex_full_text;
+
+#[cfg(test)]
+pub fn show_pattern(pattern:&Pattern) -> String {
+	let show_list = |list:&PatternList| list.items.iter().map(show_pattern).collect::<Vec<_>>().join(" ");
+	match pattern {
+		Pattern::Literal(SmallLexToken{span,tag: LexTag::Int(x)}) if *x < 0 => format!("-{}",span.fragment()),
+		Pattern::Literal(SmallLexToken{span,tag: LexTag::Float(x)}) if *x < 0.0 => format!("-{}",span.fragment()),
+		Pattern::Literal(token) => token.span.fragment().to_string(),
+		Pattern::Nil(_) => "nil".to_string(),
+		Pattern::Wildcard(_) => "_".to_string(),
+		Pattern::Bind(var) if var.count > 0 => format!("${}#{}",var.name.fragment(),var.count),
+		Pattern::Bind(var) => format!("${}",var.name.fragment()),
+		Pattern::Array(list) => format!("[{}]",show_list(list)),
+		Pattern::Tuple(list) => format!("{{{}}}",show_list(list)),
+		Pattern::Invalid(span) => format!("!{}",span.fragment()),
+	}
+}
+
+#[test]
+#[no_mangle]
+fn test_patterns() {
+	let cases = [
+		("1","1"),
+		("\"str\"","\"str\""),
+		("%ok",  "%ok"),
+		(":ok",  ":ok"),
+		("nil",  "nil"),
+		("_",    "_"),
+		("x",    "$x"),
+		("[a, _, 2]","[$a _ 2]"),
+		("{%ok, [x, {y}]}","{%ok [$x {$y}]}"),
+		("[]","[]"),
+		("-1","-1"),
+		("[- 2.5, +3]","[-2.5 3]"),
+	];
+	for (input,expected) in cases {
+		let lexed = lex_full_text(input);
+		let (pattern,error) = parse_full_pattern(TokenSlice::new(&lexed));
+		assert!(error.is_none(),"unexpected error in {:?}",input);
+		assert_eq!(show_pattern(&pattern),expected);
+	}
+
+	let lexed = lex_full_text("{a + 1, if}");
+	let (pattern,error) = parse_full_pattern(TokenSlice::new(&lexed));
+	assert_eq!(show_pattern(&pattern),"{$a !if}");
+	match error.as_deref() {
+		Some(UserSideError::Compound(errors)) => {
+			assert!(matches!(&errors[0],UserSideError::UnexpectedTokens(_)));
+			assert!(matches!(&errors[1],UserSideError::InvalidPattern(span) if *span.fragment()=="if"));
+		}
+		_ => unreachable!("expected 2 errors"),
+	}
+
+	let lexed = lex_full_text("-1");
+	let (pattern,_) = parse_full_pattern(TokenSlice::new(&lexed));
+	assert!(matches!(pattern,Pattern::Literal(SmallLexToken{tag: LexTag::Int(-1),..})));
+}
@@ -19,14 +19,57 @@ impl<'a> UserSideError<'a> {
             UserSideError::IntOverflowError(span, value) => {
                 vec![handle_int_overflow_error(span, *value)]
             }
+            UserSideError::InvalidDigit(span, radix) => vec![handle_invalid_digit(span, *radix)],
             UserSideError::UnclosedString(span, ch) => vec![handle_unclosed_string(span, *ch)],
+            UserSideError::UnclosedTripleString(start, end, ch) => vec![handle_unclosed_triple_string(start, end, *ch)],
+            UserSideError::UnclosedRawString(start, end, guards) => vec![handle_unclosed_raw_string(start, end, *guards)],
+            UserSideError::InvalidEscape(span) => vec![handle_invalid_escape(span)],
+            UserSideError::UnclosedInterpolation(start, end) => vec![handle_unclosed_interpolation(start, end)],
+            UserSideError::EmptyInterpolation(span) => vec![handle_empty_interpolation(span)],
             UserSideError::UnokwenToken(span) => vec![handle_unkowen_token_error(span)],
             UserSideError::ExtraPar(span) => vec![handle_extra_par_error(span)],
+            UserSideError::NestedTooDeep(span) => vec![handle_nested_too_deep(span)],
 
             UserSideError::UnclosedPar(start, end) => vec![handle_unclosed_par_error(start, end)],
+            UserSideError::ExpectedParen(span, ch) => vec![handle_expected_paren_error(span, *ch)],
             UserSideError::Compound(errors) => handle_compound_error(errors),
-            _=> todo!(),
-            // UserSideError::MissingFuncName(_) => todo!(),
+
+            UserSideError::MissingFuncName(span) => vec![handle_missing_func_name(span)],
+            UserSideError::EmptyFuncDef(span) => vec![handle_empty_func_def(span)],
+            UserSideError::UnexpectedNameTok(token) => vec![handle_unexpected_name_tok(&token.span)],
+            UserSideError::ReservedName(span) => vec![handle_reserved_name(span)],
+            UserSideError::SeparatedClause(span, first) => vec![handle_separated_clause(span, first)],
+            UserSideError::ClauseArity(span, first, expected, found) => vec![handle_clause_arity(span, first, *expected, *found)],
+            UserSideError::UnexpectedTokens(spans) => vec![handle_unexpected_tokens(spans)],
+            UserSideError::MissingLeftOperand(span) => vec![handle_missing_operand(span, "left")],
+            UserSideError::MissingRightOperand(span) => vec![handle_missing_operand(span, "right")],
+            UserSideError::PipeIntoNonCall(pipe, target) => vec![handle_pipe_into_non_call(pipe, target)],
+            UserSideError::MissingPathSegment(span) => vec![handle_missing_path_segment(span)],
+            UserSideError::InvalidPathRoot(span) => vec![handle_invalid_path_root(span)],
+            UserSideError::MissingField(span) => vec![handle_missing_field(span)],
+            UserSideError::MissingRangeStep(span) => vec![handle_missing_range_step(span)],
+            UserSideError::ChainedRange(first, second) => vec![handle_chained_range(first, second)],
+            UserSideError::DanglingElse(span) => vec![handle_dangling_else(span)],
+            UserSideError::MissingArmArrow(span) => vec![handle_missing_arm_arrow(span)],
+            UserSideError::MissingArmBody(span) => vec![handle_missing_arm_body(span)],
+            UserSideError::MissingMatchValue(span) => vec![handle_missing_match_value(span)],
+            UserSideError::MissingGuardCondition(span) => vec![handle_missing_guard_condition(span)],
+            UserSideError::UnparenthesizedGuard(start, end) => vec![handle_unparenthesized_guard(start, end)],
+            UserSideError::InvalidPattern(span) => vec![handle_invalid_pattern(span)],
+            UserSideError::MissingGeneratorSource(span) => vec![handle_missing_generator_source(span)],
+UserSideError::StrayLeftArrow(span) => vec![handle_stray_left_arrow(span)],
+            UserSideError::MapPercentGap(percent, brace) => vec![handle_map_percent_gap(percent, brace)],
+                        UserSideError::NoGenerator(span) => vec![handle_no_generator(span)],
+            UserSideError::TrailingComma(span) => vec![handle_trailing_comma(span)],
+            UserSideError::DuplicateComma(span) => vec![handle_duplicate_comma(span)],
+            UserSideError::EmptyGroup(span) => vec![handle_empty_group(span)],
+            UserSideError::ParenTuple(span) => vec![handle_paren_tuple(span)],
+
+            UserSideError::EmptyImport(span) => vec![handle_empty_import(span)],
+            UserSideError::MissingImportSegment(span) => vec![handle_missing_import_segment(span)],
+            UserSideError::MissingImportAlias(span) => vec![handle_missing_import_alias(span)],
+            UserSideError::InvalidImportItem(span) => vec![handle_invalid_import_item(span)],
+            UserSideError::AliasedImportItems(as_span, start) => vec![handle_aliased_import_items(as_span, start)],
         }
     }
 }
@@ -41,6 +84,18 @@ fn handle_extra_par_error(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
         .with_labels(vec![Label::primary((), start..end)])
 }
 
+// Function to create a diagnostic for NestedTooDeep
+fn handle_nested_too_deep(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Parentheses nested too deep")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("This group is inside too many others")])
+        .with_notes(vec!["pull the inner part out into a variable or a function".to_string()])
+}
+
 // Function to create a diagnostic for UnclosedPar
 fn handle_unclosed_par_error(start: &LocatedSpan<&str>, end: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
     let start_offset = start.location_offset();
@@ -56,6 +111,16 @@ fn handle_unclosed_par_error(start: &LocatedSpan<&str>, end: &LocatedSpan<&str>)
         ])
 }
 
+// Function to create a diagnostic for ExpectedParen
+fn handle_expected_paren_error(span: &LocatedSpan<&str>, ch: char) -> PrintDiagnostic<()> {
+    let end = span.location_offset() + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message(format!("Expected '{}'", ch))
+        .with_labels(vec![Label::primary((), end..end)
+            .with_message(format!("Expected '{}' after this", ch))])
+}
+
 // Function to create diagnostics for Compound errors
 fn handle_compound_error<'a>(errors: &[UserSideError<'a>]) -> Vec<PrintDiagnostic<()>> {
     let mut diagnostics = Vec::new();
@@ -99,6 +164,94 @@ fn handle_int_overflow_error(span: &LocatedSpan<&str>, value: u64) -> PrintDiagn
             .with_message("This number does not fit into an integer. Try using a float.")])
 }
 
+// Function to create a diagnostic for InvalidDigit
+fn handle_invalid_digit(span: &LocatedSpan<&str>, radix: u32) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+    let label = match span.fragment().is_empty() {
+        true => format!("Expected a base {} digit after the prefix", radix),
+        false => format!("'{}' is not a base {} digit", span.fragment(), radix),
+    };
+
+    PrintDiagnostic::error()
+        .with_message("Invalid digit in number literal")
+        .with_labels(vec![Label::primary((), start..end).with_message(label)])
+}
+
+// Function to create a diagnostic for UnclosedTripleString
+fn handle_unclosed_triple_string(start: &LocatedSpan<&str>, end: &LocatedSpan<&str>, ch: char) -> PrintDiagnostic<()> {
+    let start_offset = start.location_offset();
+    let end_offset = end.location_offset();
+    let quotes: String = [ch; 3].iter().collect();
+
+    PrintDiagnostic::error()
+        .with_message("Unclosed multi-line string")
+        .with_labels(vec![
+            Label::primary((), start_offset..start_offset + start.fragment().len())
+                .with_message("Opened here"),
+            Label::secondary((), end_offset..end_offset)
+                .with_message(format!("Reached the end of the file looking for {}", quotes)),
+        ])
+}
+
+// Function to create a diagnostic for UnclosedRawString
+fn handle_unclosed_raw_string(start: &LocatedSpan<&str>, end: &LocatedSpan<&str>, guards: usize) -> PrintDiagnostic<()> {
+    let start_offset = start.location_offset();
+    let end_offset = end.location_offset();
+    let closer = format!("\"{}", "#".repeat(guards));
+
+    PrintDiagnostic::error()
+        .with_message("Unclosed raw string")
+        .with_labels(vec![
+            Label::primary((), start_offset..start_offset + start.fragment().len())
+                .with_message("Opened here"),
+            Label::secondary((), end_offset..end_offset)
+                .with_message(format!("Reached the end of the file looking for {}", closer)),
+        ])
+}
+
+// Function to create a diagnostic for UnclosedInterpolation
+fn handle_unclosed_interpolation(start: &LocatedSpan<&str>, end: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start_offset = start.location_offset();
+    let end_offset = end.location_offset() + end.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Unclosed interpolation")
+        .with_labels(vec![
+            Label::primary((), start_offset..start_offset + start.fragment().len())
+                .with_message("Opened here"),
+            Label::primary((), end_offset..end_offset)
+                .with_message("Expected a '}' here"),
+        ])
+        .with_notes(vec!["an interpolation has to be closed on the line it starts".to_string()])
+}
+
+// Function to create a diagnostic for EmptyInterpolation
+fn handle_empty_interpolation(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Empty interpolation")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected an expression inside")])
+        .with_notes(vec!["use \\#{ for a literal #{".to_string()])
+}
+
+// Function to create a diagnostic for InvalidEscape
+fn handle_invalid_escape(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message(format!("Invalid escape sequence '{}'", span.fragment()))
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Not a valid escape")])
+        .with_notes(vec![
+            "Valid escapes are \\n \\t \\r \\0 \\\\ \\\" \\' \\# \\xNN (ascii) and \\u{...} (1 to 6 hex digits).".to_string(),
+        ])
+}
+
 // Function to create a diagnostic for UnclosedString
 fn handle_unclosed_string(span: &LocatedSpan<&str>, ch: char) -> PrintDiagnostic<()> {
     let start = span.location_offset();
@@ -113,6 +266,423 @@ fn handle_unclosed_string(span: &LocatedSpan<&str>, ch: char) -> PrintDiagnostic
         ])
 }
 
+// Function to create a diagnostic for MissingFuncName
+fn handle_missing_func_name(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing function name")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a name after this")])
+}
+
+// Function to create a diagnostic for EmptyFuncDef
+fn handle_empty_func_def(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Empty function definition")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a name, parameters and a body")])
+}
+
+// Function to create a diagnostic for UnexpectedNameTok
+fn handle_unexpected_name_tok(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Invalid function name")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Function names must be words")])
+}
+
+// Function to create a diagnostic for ReservedName
+fn handle_reserved_name(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message(format!("'{}' is a reserved keyword", span.fragment()))
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Keywords can not be used as names")])
+}
+
+// Function to create a diagnostic for SeparatedClause
+fn handle_separated_clause(span: &LocatedSpan<&str>, first: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let first_start = first.location_offset();
+
+    PrintDiagnostic::error()
+        .with_message(format!("Clauses of '{}' are not next to each other", span.fragment()))
+        .with_labels(vec![
+            Label::primary((), start..start + span.fragment().len())
+                .with_message("This clause is separated from the others"),
+            Label::secondary((), first_start..first_start + first.fragment().len())
+                .with_message("First defined here"),
+        ])
+        .with_notes(vec![
+            "All clauses of a function must come one after the other.".to_string(),
+        ])
+}
+
+// Function to create a diagnostic for ClauseArity
+fn handle_clause_arity(span: &LocatedSpan<&str>, first: &LocatedSpan<&str>, expected: usize, found: usize) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let first_start = first.location_offset();
+
+    PrintDiagnostic::error()
+        .with_message(format!("Clause of '{}' takes {} arguments but the first one takes {}", span.fragment(), found, expected))
+        .with_labels(vec![
+            Label::primary((), start..start + span.fragment().len())
+                .with_message(format!("Expected {} arguments", expected)),
+            Label::secondary((), first_start..first_start + first.fragment().len())
+                .with_message("First clause here"),
+        ])
+}
+
+// Function to create a diagnostic for UnexpectedTokens
+fn handle_unexpected_tokens(spans: &[LocatedSpan<&str>]) -> PrintDiagnostic<()> {
+    let labels = match (spans.first(), spans.last()) {
+        (Some(first), Some(last)) => {
+            let start = first.location_offset();
+            let end = last.location_offset() + last.fragment().len();
+            vec![Label::primary((), start..end)]
+        }
+        _ => Vec::new(),
+    };
+
+    PrintDiagnostic::error()
+        .with_message("Unexpected tokens")
+        .with_labels(labels)
+}
+
+// Function to create a diagnostic for MissingLeftOperand and MissingRightOperand
+fn handle_missing_operand(span: &LocatedSpan<&str>, side: &str) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message(format!("Missing {} operand", side))
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message(format!("Expected an expression on the {} of this operator", side))])
+}
+
+// Function to create a diagnostic for PipeIntoNonCall
+fn handle_pipe_into_non_call(pipe: &LocatedSpan<&str>, target: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let pipe_start = pipe.location_offset();
+    let target_start = target.location_offset();
+
+    PrintDiagnostic::error()
+        .with_message("Piping into something that is not a function call")
+        .with_labels(vec![
+            Label::primary((), target_start..target_start + target.fragment().len())
+                .with_message("Expected a call like f(...) here"),
+            Label::secondary((), pipe_start..pipe_start + pipe.fragment().len())
+                .with_message("Piped here"),
+        ])
+        .with_notes(vec![
+            "The piped value is passed as the first argument, so x |> f(a) means f(x, a).".to_string(),
+        ])
+}
+
+// Function to create a diagnostic for DanglingElse
+fn handle_dangling_else(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("else without a matching if")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("This else does not follow an if block")])
+        .with_notes(vec![
+            "An else must come right after the closing } of an if, with no ; in between.".to_string(),
+        ])
+}
+
+// Function to create a diagnostic for MissingArmArrow
+fn handle_missing_arm_arrow(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let end = span.location_offset() + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing '=>' in arm")
+        .with_labels(vec![Label::primary((), end..end)
+            .with_message("Expected '=>' followed by the arm body")])
+}
+
+// Function to create a diagnostic for MissingArmBody
+fn handle_missing_arm_body(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing arm body")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected an expression after this")])
+}
+
+// Function to create a diagnostic for MissingMatchValue
+fn handle_missing_match_value(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing value to match on")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected an expression after this")])
+}
+
+// Function to create a diagnostic for MissingGuardCondition
+fn handle_missing_guard_condition(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing guard condition")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a condition after this")])
+}
+
+// Function to create a diagnostic for UnparenthesizedGuard
+fn handle_unparenthesized_guard(start: &LocatedSpan<&str>, end: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start_offset = start.location_offset();
+    let end_offset = end.location_offset() + end.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Guard condition needs parentheses")
+        .with_labels(vec![Label::primary((), start_offset..end_offset)
+            .with_message("Wrap this in ( )")])
+        .with_notes(vec!["def guards are written like if (n > 0), bare conditions are only for match arms".to_string()])
+}
+
+// Function to create a diagnostic for MapPercentGap
+fn handle_map_percent_gap(percent: &LocatedSpan<&str>, brace: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = percent.location_offset();
+    let end = brace.location_offset() + brace.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Space inside %{")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("A map starts with %{ written together")])
+}
+
+// Function to create a diagnostic for StrayLeftArrow
+fn handle_stray_left_arrow(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("<- outside of a for")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("<- only binds generators like for (x <- xs)")])
+        .with_notes(vec!["for a comparison with a negative number put a space in: x < -1".to_string()])
+}
+
+// Function to create a diagnostic for InvalidPattern
+fn handle_invalid_pattern(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Invalid pattern")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a literal, a name, _, [...] or {...}")])
+}
+
+// Function to create a diagnostic for MissingGeneratorSource
+fn handle_missing_generator_source(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Generator without a source")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected something to take values from after this")])
+}
+
+// Function to create a diagnostic for NoGenerator
+fn handle_no_generator(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("for without a generator")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected at least one 'pattern <- source' in the ()")])
+        .with_notes(vec!["for example: for (x <- xs) { x * 2 }".to_string()])
+}
+
+// Function to create a diagnostic for MissingPathSegment
+fn handle_missing_path_segment(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing name after '::'")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a name after this")])
+}
+
+// Function to create a diagnostic for InvalidPathRoot
+fn handle_invalid_path_root(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("'::' can only follow a name")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("The left side of this is not a module name")])
+        .with_notes(vec!["use '.' to access a field of a value".to_string()])
+}
+
+// Function to create a diagnostic for MissingRangeStep
+fn handle_missing_range_step(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing range step")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a step after this")])
+        .with_notes(vec!["a range with a step looks like 0..10:2".to_string()])
+}
+
+// Function to create a diagnostic for ChainedRange
+fn handle_chained_range(first: &LocatedSpan<&str>, second: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let first_start = first.location_offset();
+    let second_start = second.location_offset();
+
+    PrintDiagnostic::error()
+        .with_message("Ranges can not be chained")
+        .with_labels(vec![
+            Label::primary((), second_start..second_start + second.fragment().len())
+                .with_message("This range starts with another range"),
+            Label::secondary((), first_start..first_start + first.fragment().len())
+                .with_message("The first range is here"),
+        ])
+        .with_notes(vec!["use parens like (a..b)..c if this is what you meant".to_string()])
+}
+
+// Function to create a diagnostic for MissingField
+fn handle_missing_field(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing field name after '.'")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a name after this")])
+}
+
+// Function to create a diagnostic for TrailingComma
+fn handle_trailing_comma(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Trailing comma")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("No item follows this comma")])
+}
+
+// Function to create a diagnostic for DuplicateComma
+fn handle_duplicate_comma(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Unexpected comma")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("No item before this comma")])
+}
+
+// Function to create a diagnostic for EmptyGroup
+fn handle_empty_group(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Empty parenthesis")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected an expression inside")])
+        .with_notes(vec!["use {} for an empty tuple".to_string()])
+}
+
+// Function to create a diagnostic for ParenTuple
+fn handle_paren_tuple(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Parenthesis only group a single expression")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("This holds more than one item")])
+        .with_notes(vec!["tuples are written with braces like {a, b}".to_string()])
+}
+
+// Function to create a diagnostic for EmptyImport
+fn handle_empty_import(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Empty import")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a module path after this")])
+}
+
+// Function to create a diagnostic for MissingImportSegment
+fn handle_missing_import_segment(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let end = span.location_offset() + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing name in import path")
+        .with_labels(vec![Label::primary((), end..end)
+            .with_message("Expected a name here")])
+        .with_notes(vec![
+            "Import paths look like a::b::c, a::b as x or a::b::{f, g}".to_string(),
+        ])
+}
+
+// Function to create a diagnostic for MissingImportAlias
+fn handle_missing_import_alias(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Missing import alias")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Expected a name after this")])
+}
+
+// Function to create a diagnostic for InvalidImportItem
+fn handle_invalid_import_item(span: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let start = span.location_offset();
+    let end = start + span.fragment().len();
+
+    PrintDiagnostic::error()
+        .with_message("Invalid import item")
+        .with_labels(vec![Label::primary((), start..end)
+            .with_message("Only names can be imported")])
+}
+
+// Function to create a diagnostic for AliasedImportItems
+fn handle_aliased_import_items(as_span: &LocatedSpan<&str>, start: &LocatedSpan<&str>) -> PrintDiagnostic<()> {
+    let as_start = as_span.location_offset();
+    let items_start = start.location_offset();
+
+    PrintDiagnostic::error()
+        .with_message("Alias on a selective import")
+        .with_labels(vec![
+            Label::primary((), as_start..as_start + as_span.fragment().len())
+                .with_message("Nothing to alias here"),
+            Label::secondary((), items_start..items_start + start.fragment().len())
+                .with_message("These items are imported under their own names"),
+        ])
+}
+
 // Function to print errors to standard output
 #[allow(dead_code)]
 pub fn print_errors_to_stdout<'a>(
@@ -0,0 +1,270 @@
+use crate::ast::{GrammerNode,GrammerNodeBase,Value,Varible,Func,Lamda,Block,ParenExpr,FuncDef,Pattern,ListExpr,ForClause};
+
+use std::collections::HashMap;
+
+/*
+names can be rebound like in elixir
+
+ def f(x) {      x#1
+   x = x + 1;    x#2 = x#1 + 1
+   g = fn(x) {   x#3
+     x           x#3
+   };
+   x             x#2
+ }
+
+every binding inside a def gets its own number (starting at 1) so x#1 and x#2 can be told apart later.
+a use gets the number of the binding it sees, 0 means it is not bound in this def (globals, other functions or a typo).
+bodies of if/else, lamdas, match arms and for are their own scope so bindings made there do not leak out.
+*/
+
+struct Scopes<'a> {
+	counters: HashMap<&'a str,usize>, //shared by the whole def
+	stack: Vec<HashMap<&'a str,usize>>,
+}
+
+impl<'a> Scopes<'a> {
+	fn new() -> Self {
+		Scopes{counters: HashMap::new(),stack: vec![HashMap::new()]}
+	}
+
+	fn push(&mut self) {
+		self.stack.push(HashMap::new());
+	}
+
+	fn pop(&mut self) {
+		self.stack.pop();
+	}
+
+	fn bind(&mut self,var:&mut Varible<'a>) {
+		let name = *var.name.fragment();
+		let count = self.counters.entry(name).or_insert(0);
+		*count += 1;
+		var.count = *count;
+		self.stack.last_mut().unwrap().insert(name,*count);
+	}
+
+	fn lookup(&self,var:&mut Varible<'a>) {
+		let name = var.name.fragment();
+		var.count = self.stack.iter().rev()
+			.find_map(|scope| scope.get(name).copied())
+			.unwrap_or(0);
+	}
+}
+
+//numbers every binding of a top level node. safe to run on nodes with errors
+pub fn number_bindings(node:&mut GrammerNode) {
+	walk_node(node,&mut Scopes::new());
+}
+
+fn walk_node<'a>(node:&mut GrammerNode<'a,'_>,scopes:&mut Scopes<'a>) {
+	match &mut node.base {
+		GrammerNodeBase::Unprocessed(_)
+		| GrammerNodeBase::KeyWord(_)
+		| GrammerNodeBase::Import(_) => {},
+
+		GrammerNodeBase::Paren(paren) => walk_paren(paren,scopes),
+		GrammerNodeBase::ControlBlock(block) => walk_block(block,scopes),
+		GrammerNodeBase::Else(else_block) => walk_scoped(&mut else_block.body,scopes),
+		GrammerNodeBase::Sequence(nodes) => nodes.iter_mut().for_each(|x| walk_node(x,scopes)),
+		GrammerNodeBase::Val(value) => walk_value(value,scopes),
+		GrammerNodeBase::Return(ret) => if let Some(value) = &mut ret.value {
+			walk_value(value,scopes);
+		},
+		//the right side still sees the old binding
+		GrammerNodeBase::Declare(assign) => {
+			if let Some(value) = &mut assign.right {
+				walk_value(value,scopes);
+			}
+			scopes.bind(&mut assign.left);
+		},
+		GrammerNodeBase::Function(def) => walk_def(def),
+	}
+}
+
+fn walk_value<'a>(value:&mut Value<'a,'_>,scopes:&mut Scopes<'a>) {
+	match value {
+		Value::Basic(_) | Value::Nil(_) => {},
+		Value::Var(var) => scopes.lookup(var),
+		Value::Path(_) => {},
+		Value::Member(member) => walk_node(&mut member.value,scopes),
+		Value::Paren(paren) => walk_paren(paren,scopes),
+		Value::Unary(op) => {
+			if let Some(operand) = &mut op.operand {
+				walk_node(operand,scopes);
+			}
+		},
+		Value::Op(op) => {
+			if let Some(left) = &mut op.left {
+				walk_node(left,scopes);
+			}
+			if let Some(right) = &mut op.right {
+				walk_node(right,scopes);
+			}
+		},
+		Value::Call(call) => {
+			if let Some(piped) = &mut call.piped {
+				walk_value(piped,scopes);
+			}
+			match &mut call.func {
+				Func::Defed(_) | Func::Pathed(_) | Func::Concat(_) => {},
+				Func::Vared(var) => scopes.lookup(var),
+				Func::Anon(lamda) => walk_lamda(lamda,scopes),
+				Func::Computed(node) => walk_node(node,scopes),
+			}
+			walk_list(&mut call.args,scopes);
+		},
+		Value::If(if_node) => {
+			walk_block(&mut if_node.body,scopes);
+			if let Some(else_block) = &mut if_node.else_block {
+				walk_scoped(&mut else_block.body,scopes);
+			}
+		},
+		Value::Func(lamda) => walk_lamda(lamda,scopes),
+		Value::Cond(cond) => for arm in cond.arms.iter_mut() {
+			walk_node(&mut arm.condition,scopes);
+			if let Some(body) = &mut arm.body {
+				scopes.push();
+				walk_node(body,scopes);
+				scopes.pop();
+			}
+		},
+		Value::Match(match_node) => {
+			if let Some(value) = &mut match_node.value {
+				walk_node(value,scopes);
+			}
+			for arm in match_node.arms.iter_mut() {
+				scopes.push();
+				bind_pattern(&mut arm.pattern,scopes);
+				if let Some(condition) = arm.guard.as_mut().and_then(|x| x.condition.as_mut()) {
+					walk_node(condition,scopes);
+				}
+				if let Some(body) = &mut arm.body {
+					walk_node(body,scopes);
+				}
+				scopes.pop();
+			}
+		},
+		Value::Map(map) => for entry in map.entries.iter_mut() {
+			walk_node(&mut entry.key,scopes);
+			if let Some(value) = &mut entry.value {
+				walk_node(value,scopes);
+			}
+		},
+		Value::Array(list) | Value::Tuple(list) => walk_list(list,scopes),
+		Value::Range(range) => {
+			walk_node(&mut range.start,scopes);
+			if let Some(end) = &mut range.end {
+				walk_node(end,scopes);
+			}
+			if let Some(step) = range.step.as_mut().and_then(|x| x.value.as_mut()) {
+				walk_node(step,scopes);
+			}
+		},
+		//every generator sees the ones before it
+		Value::For(comprehension) => {
+			scopes.push();
+			for clause in comprehension.clauses.iter_mut() {
+				match clause {
+					ForClause::Generator(generator) => {
+						if let Some(source) = &mut generator.source {
+							walk_node(source,scopes);
+						}
+						bind_pattern(&mut generator.pattern,scopes);
+					}
+					ForClause::Filter(filter) => walk_node(filter,scopes),
+				}
+			}
+			walk_paren(&mut comprehension.body,scopes);
+			scopes.pop();
+		},
+	}
+}
+
+fn walk_paren<'a>(paren:&mut ParenExpr<'a,'_>,scopes:&mut Scopes<'a>) {
+	if let Some(body) = &mut paren.body {
+		walk_node(body,scopes);
+	}
+}
+
+fn walk_list<'a>(list:&mut ListExpr<'a,'_>,scopes:&mut Scopes<'a>) {
+	list.items.iter_mut().for_each(|x| walk_node(&mut x.value,scopes));
+}
+
+fn walk_scoped<'a>(paren:&mut ParenExpr<'a,'_>,scopes:&mut Scopes<'a>) {
+	scopes.push();
+	walk_paren(paren,scopes);
+	scopes.pop();
+}
+
+//the (...) part is evaluated in the outer scope and the {...} part gets its own
+fn walk_block<'a>(block:&mut Block<'a,'_>,scopes:&mut Scopes<'a>) {
+	walk_paren(&mut block.start,scopes);
+	walk_scoped(&mut block.body,scopes);
+}
+
+//lamdas share the counters of the def they are in
+fn walk_lamda<'a>(lamda:&mut Lamda<'a,'_>,scopes:&mut Scopes<'a>) {
+	scopes.push();
+	lamda.params.items.iter_mut().for_each(|x| bind_pattern(x,scopes));
+	walk_paren(&mut lamda.body,scopes);
+	scopes.pop();
+}
+
+//every clause is numbered on its own since they never see each other's bindings
+fn walk_def(def:&mut FuncDef) {
+	for clause in def.clauses.iter_mut() {
+		let scopes = &mut Scopes::new();
+		clause.params.items.iter_mut().for_each(|x| bind_pattern(x,scopes));
+		if let Some(condition) = clause.guard.as_mut().and_then(|x| x.condition.as_mut()) {
+			walk_node(condition,scopes);
+		}
+		walk_paren(&mut clause.body,scopes);
+	}
+}
+
+fn bind_pattern<'a>(pattern:&mut Pattern<'a>,scopes:&mut Scopes<'a>) {
+	match pattern {
+		Pattern::Bind(var) => scopes.bind(var),
+		Pattern::Array(list) | Pattern::Tuple(list) => list.items.iter_mut().for_each(|x| bind_pattern(x,scopes)),
+		Pattern::Literal(_) | Pattern::Nil(_) | Pattern::Wildcard(_) | Pattern::Invalid(_) => {},
+	}
+}
+
+#[cfg(test)]
+use crate::{lex_full_text,token::TokenSlice,parse::parse,expr::show};
+
+#[cfg(test)]
+fn numbered(text:&str) -> String {
+	let lexed = lex_full_text(text);
+	let (_,node) = parse(TokenSlice::new(&lexed)).unwrap();
+	assert!(node.error.is_none(),"unexpected error in {:?}",text);
+	show(&node)
+}
+
+#[test]
+#[no_mangle]
+fn test_number_bindings() {
+	assert_eq!(
+		numbered("def f(x) { x = x + 1; g = fn(x) { x }; x = x * 2; x }"),
+		"def($x#1){x#2=(+ x#1 1) g#1=fn($x#3){x#3} x#4=(* x#2 2) x#4}"
+	);
+
+	//bindings inside an if or a match arm do not leak and unknowen names stay 0
+	assert_eq!(
+		numbered("def f(a) { if (a) { b = 1; b } else { b }; match a { {b, _} => b, _ => b } }"),
+		"def($a#1){if(a#1){b#1=1 b#1}else {b} match a#1{{$b#2 _} => b#2, _ => b}}"
+	);
+
+	//generators bind for the filters and generators after them and for the body
+	assert_eq!(
+		numbered("def f(xs) { for (x <- xs, y <- g(x), x < y) { x + y }; x }"),
+		"def($xs#1){for($x#1 <- xs#1, $y#1 <- g[x#1], (< x#1 y#1)){(+ x#1 y#1)} x}"
+	);
+
+	//every clause starts counting again
+	assert_eq!(
+		numbered("def f({x, _}) if (x > 0) { x } def f(x) { x = 2; x }"),
+		"def({$x#1 _}) if((> x#1 0)){x#1} def($x#1){x#2=2 x#2}"
+	);
+}
@@ -20,10 +20,10 @@ pub struct LexToken<'a> {
 
 impl<'a> LexToken<'a> {
     pub fn new(span: LocatedSpan<&'a str>,tag : LexTag) -> Self {
-        LexToken { span: span, tag:tag , error:None}
+        LexToken { span, tag , error:None}
     }
      pub fn err_new(span: LocatedSpan<&'a str>,tag : LexTag, error:UserSideError<'a>) -> Self {
-        LexToken { span: span, tag:tag , error:Some(Box::new(error))}
+        LexToken { span, tag , error:Some(Box::new(error))}
     }
 }
 
@@ -45,8 +45,13 @@ pub enum LexTag {
     Delimiter(char),
     Ender(char),
     Op(BinaryOp),
-    String(char),
+    String(char,Box<str>), //the quote and the contents with escapes decoded
     PoisonString(char),
+    RawString(usize,Box<str>), //r##"..."## with the number of # guards. the contents are taken as is
+    //"a #{b} c" is StringStart("a ") Delimiter('{') b Delimiter('}') StringEnd(" c"). holes in between are StringMiddle
+    StringStart(Box<str>),
+    StringMiddle(Box<str>),
+    StringEnd(Box<str>),
     Unknowen(),
 }
 
@@ -55,6 +60,7 @@ pub enum BinaryOp {
     // Your BinaryOp variants here
     Pipe,
     Dot,
+    Range,
     Dots,
     DoubleDots,
     Add,
@@ -65,6 +71,7 @@ pub enum BinaryOp {
     Exp,
     FatArrow,
     SmallArrow,
+    LeftArrow,
     SingleOr,
     Or,
     And,
@@ -245,6 +252,6 @@ use nom::FindToken;
 
 impl<'a, 'b> FindToken<LexToken<'a>> for TokenSlice<'a, 'b> {
     fn find_token(&self, token: LexToken<'a>) -> bool {
-        self.tokens.iter().any(|t| *t == token)
+        self.tokens.contains(&token)
     }
 }
@@ -0,0 +1,383 @@
+use crate::token::{TokenSlice,LexToken,LexTag};
+use crate::errors::UserSideError;
+use crate::parse::{peek,is_opener,get_closer};
+
+use nom::{InputIter,InputLength,InputTake,Slice};
+
+/*
+the delimiter pass. runs over the raw tokens before parsing and makes sure ()[]{} balance.
+the parser then reads its input as token trees: a group is an opener, the trees inside it
+and its closer. take_paren, skip_till and split_outer all walk trees so a keyword or ender
+inside a group is never seen from the outside.
+
+unbalanced input is fixed up here so the parser only ever sees balanced groups:
+ - a closer nobody opened becomes an Unknowen token carrying ExtraPar
+ - an opener that is never closed gets an empty closer token carrying UnclosedPar.
+   we guess where it should have been closed:
+     before the first ; inside a () or [] since those never hold statements
+     before the first line that is indented no deeper than the line the opener is on
+   if neither shows up it is closed right before whatever made us give up on it
+   (the end of the file or a closer that belongs to an outer group).
+ - an opener nested deeper than MAX_DEPTH carries NestedTooDeep and the delimiters of its group
+   become plain tokens, so nothing after this pass recurses further than that.
+
+ def f(x) {
+     y = g(x;        <- ( closed before the ;
+     y
+ def h() {}          <- { closed before this line
+*/
+
+pub enum TokenTree<'a,'b> {
+	Leaf(&'b LexToken<'a>),
+	Group(Group<'a,'b>),
+}
+
+pub struct Group<'a,'b> {
+	pub open: &'b LexToken<'a>,
+	pub body: TokenSlice<'a,'b>, //everything between open and close. token_trees on it gives the nested trees
+	pub close: Option<&'b LexToken<'a>>, //only None if the input was never balanced
+}
+
+impl<'a,'b> TokenTree<'a,'b> {
+	//how many tokens this tree covers
+	pub fn len(&self) -> usize {
+		match self {
+			TokenTree::Leaf(_) => 1,
+			TokenTree::Group(group) => 1 + group.body.input_len() + group.close.is_some() as usize,
+		}
+	}
+}
+
+//the trees of a slice one after the other. groups only find their closer when they are reached
+//so a parser that stops early never walks the rest of the input
+pub struct TokenTrees<'a,'b> {
+	rest: TokenSlice<'a,'b>,
+}
+
+impl<'a,'b> TokenTrees<'a,'b> {
+	//whatever comes after the trees taken so far
+	pub fn rest(&self) -> TokenSlice<'a,'b> {
+		self.rest.clone()
+	}
+}
+
+impl<'a,'b> Iterator for TokenTrees<'a,'b> {
+	type Item = TokenTree<'a,'b>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let token = peek(&self.rest)?;
+		let tree = match token.tag {
+			LexTag::Delimiter(c) if is_opener(c) => match find_closer(&self.rest) {
+				Some(i) => {
+					let group = Group{open: token,body: self.rest.slice(1..i),close: peek(&self.rest.slice(i..))};
+					self.rest = self.rest.take_split(i+1).0;
+					group
+				}
+				None => {
+					let group = Group{open: token,body: self.rest.slice(1..),close: None};
+					self.rest = self.rest.take_split(self.rest.input_len()).0;
+					group
+				}
+			},
+			_ => {
+				self.rest = self.rest.take_split(1).0;
+				return Some(TokenTree::Leaf(token));
+			}
+		};
+		Some(TokenTree::Group(tree))
+	}
+}
+
+//closers with nothing open are left as leaves
+pub fn token_trees<'a,'b>(input:TokenSlice<'a,'b>) -> TokenTrees<'a,'b> {
+	TokenTrees{rest: input}
+}
+
+//assumes input starts with an opener. the index of the closer that matches it
+fn find_closer(input:&TokenSlice) -> Option<usize> {
+	let mut depth = 0usize;
+	for (i,token) in input.iter_indices() {
+		if let LexTag::Delimiter(c) = token.tag {
+			if is_opener(c) {
+				depth+=1;
+			} else {
+				depth-=1;
+				if depth == 0 {
+					return Some(i);
+				}
+			}
+		}
+	}
+	None
+}
+
+//groups nested deeper than this are not walked. the parser recurses once per group
+//so anything past it would overflow the stack there if not here
+const MAX_DEPTH :usize = 32;
+
+//the recovery pass. every opener in the result has a matching closer
+pub fn balance(tokens:Vec<LexToken>) -> Vec<LexToken> {
+	let mut balancer = Balancer::new(&tokens);
+	let mut i = 0;
+	while i < tokens.len() {
+		i = match tokens[i].tag {
+			LexTag::Delimiter(c) if is_opener(c) => balancer.group(i,&mut Vec::new()),
+			LexTag::Delimiter(_) => {
+				balancer.marks[i] = Mark::Extra;
+				i+1
+			}
+			_ => i+1,
+		};
+	}
+
+	let Balancer{marks,inserts,..} = balancer;
+	let mut inserts = inserts.into_iter().peekable();
+	let mut ans = Vec::with_capacity(tokens.len()+inserts.len());
+	for (i,token) in tokens.into_iter().enumerate() {
+		while let Some((_,closer)) = inserts.next_if(|(pos,_)| *pos == i) {
+			ans.push(closer);
+		}
+		ans.push(match marks[i] {
+			Mark::Keep => token,
+			Mark::Extra => LexToken::err_new(token.span,LexTag::Unknowen(),UserSideError::ExtraPar(token.span)),
+			Mark::TooDeep => LexToken::err_new(token.span,LexTag::Unknowen(),UserSideError::NestedTooDeep(token.span)),
+			Mark::Flat => LexToken::new(token.span,LexTag::Unknowen()),
+		});
+	}
+	ans.extend(inserts.map(|(_,closer)| closer));
+	ans
+}
+
+//what happens to a token once the pass is done
+#[derive(Clone,Copy)]
+enum Mark {
+	Keep,
+	Extra, //a closer nobody opened
+	TooDeep, //an opener past MAX_DEPTH
+	Flat, //a delimiter inside a group that was too deep
+}
+
+struct Balancer<'t,'a> {
+	tokens: &'t [LexToken<'a>],
+	indents: Vec<usize>, //indentation of the line each token is on
+	starts_line: Vec<bool>,
+	marks: Vec<Mark>,
+	inserts: Vec<(usize,LexToken<'a>)>, //closers to put before the token at that index. in order
+}
+
+impl<'t,'a> Balancer<'t,'a> {
+	fn new(tokens:&'t [LexToken<'a>]) -> Self {
+		let mut indents = Vec::with_capacity(tokens.len());
+		let mut starts_line = Vec::with_capacity(tokens.len());
+		let mut line = 0;
+		let mut indent = 0;
+		for token in tokens {
+			let starts = token.span.location_line() != line;
+			if starts {
+				indent = token.span.get_utf8_column();
+			}
+			//multi line tokens like strings end on a later line than they start
+			line = token.span.location_line() + token.span.fragment().matches('\n').count() as u32;
+			indents.push(indent);
+			starts_line.push(starts);
+		}
+
+		Balancer{
+			tokens,
+			indents,
+			starts_line,
+			marks: vec![Mark::Keep;tokens.len()],
+			inserts: Vec::new(),
+		}
+	}
+
+	//walks the group opened at open and returns the index right after it.
+	//enclosing are the openers of the groups we are inside of
+	fn group(&mut self,open:usize,enclosing:&mut Vec<char>) -> usize {
+		let opener = match self.tokens[open].tag {
+			LexTag::Delimiter(c) => c,
+			_ => return open+1,
+		};
+
+		let mut cut = None;
+		let mut i = open+1;
+		while i < self.tokens.len() {
+			//a ; can never be inside a () or [] so we give up right away.
+			//otherwise a stray closer later on could be taken as ours
+			if opener != '{' && self.tokens[i].tag == LexTag::Ender(';') {
+				return self.unclosed(open,i);
+			}
+			//a dedent is only a guess since badly indented code can still be balanced
+			if cut.is_none() && self.starts_line[i] && self.indents[i] <= self.indents[open] {
+				cut = Some(i);
+			}
+
+			i = match self.tokens[i].tag {
+				LexTag::Delimiter(c) if is_opener(c) && enclosing.len()+2 > MAX_DEPTH => self.too_deep(i),
+				LexTag::Delimiter(c) if is_opener(c) => {
+					enclosing.push(opener);
+					let next = self.group(i,enclosing);
+					enclosing.pop();
+					next
+				}
+				LexTag::Delimiter(c) if c == get_closer(opener) => return i+1,
+				LexTag::Delimiter(c) if enclosing.iter().any(|x| get_closer(*x) == c) => break,
+				LexTag::Delimiter(_) => {
+					self.marks[i] = Mark::Extra;
+					i+1
+				}
+				_ => i+1,
+			};
+		}
+
+		self.unclosed(open,cut.unwrap_or(i))
+	}
+
+	//steps over a group nested past MAX_DEPTH without recursing.
+	//its opener is reported and every delimiter up to its closer is kept as a plain token
+	fn too_deep(&mut self,open:usize) -> usize {
+		self.marks[open] = Mark::TooDeep;
+		let mut depth = 0usize;
+		for i in open..self.tokens.len() {
+			if let LexTag::Delimiter(c) = self.tokens[i].tag {
+				match is_opener(c) {
+					true => depth+=1,
+					false => depth-=1,
+				}
+				if i != open {
+					self.marks[i] = Mark::Flat;
+				}
+				if depth == 0 {
+					return i+1;
+				}
+			}
+		}
+		self.tokens.len()
+	}
+
+	//closes the group at cut. whatever came after it is walked again by the parent
+	fn unclosed(&mut self,open:usize,cut:usize) -> usize {
+		self.inserts.retain(|(pos,_)| *pos <= cut);
+		self.marks[cut..].iter_mut().for_each(|x| *x = Mark::Keep);
+
+		let start = self.tokens[open].span;
+		let last = self.tokens[cut-1].span;
+		let closer = match self.tokens[open].tag {
+			LexTag::Delimiter(c) => get_closer(c),
+			_ => return cut,
+		};
+		let (empty,_) = last.take_split(last.fragment().len());
+		self.inserts.push((cut,LexToken::err_new(empty,LexTag::Delimiter(closer),UserSideError::UnclosedPar(start,last))));
+		cut
+	}
+}
+
+#[cfg(test)]
+use crate::lex_full_text;
+
+#[cfg(test)]
+fn shape(tokens:&[LexToken]) -> String {
+	tokens.iter().map(|t| match t.tag {
+		LexTag::Delimiter(c) if t.span.fragment().is_empty() => format!("<{}>",c),
+		LexTag::Unknowen() => format!("!{}",t.span.fragment()),
+		_ => t.span.fragment().to_string(),
+	}).collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+#[no_mangle]
+fn test_balance() {
+	let cases = [
+		("f(a, [b]) { c }","f ( a , [ b ] ) { c }"),
+		("a ) b","a !) b"),
+		("{ a ]","{ a !] <}>"),
+		("x = g(y; z","x = g ( y <)> ; z"),
+		("{ ( }","{ ( <)> }"),
+		("def f(x) {\n\ty = g(x;\n\ty\ndef h() {}","def f ( x ) { y = g ( x <)> ; y <}> def h ( ) { }"),
+	];
+	for (input,expected) in cases {
+		let balanced = balance(lex_full_text(input));
+		assert_eq!(shape(&balanced),expected,"in {:?}",input);
+	}
+
+	let balanced = balance(lex_full_text("f(a, b"));
+	match TokenSlice::last(&TokenSlice::new(&balanced)).and_then(|t| t.error.as_deref()) {
+		Some(UserSideError::UnclosedPar(start,end)) => {
+			assert_eq!(*start.fragment(),"(");
+			assert_eq!(*end.fragment(),"b");
+		}
+		_ => unreachable!("expected an unclosed paren"),
+	}
+}
+
+#[test]
+#[no_mangle]
+fn test_token_trees() {
+	let lexed = balance(lex_full_text("a (b [c] d) e"));
+	let trees = token_trees(TokenSlice::new(&lexed)).collect::<Vec<_>>();
+	assert_eq!(trees.len(),3);
+	assert_eq!(trees.iter().map(|x| x.len()).sum::<usize>(),lexed.len());
+	match &trees[1] {
+		TokenTree::Group(group) => {
+			let inner = token_trees(group.body.clone()).collect::<Vec<_>>();
+			assert_eq!(inner.len(),3);
+			assert!(matches!(&inner[1],TokenTree::Group(nested) if nested.body.input_len()==1 && nested.close.is_some()));
+			assert!(matches!(group.close,Some(close) if *close.span.fragment()==")"));
+		}
+		TokenTree::Leaf(_) => unreachable!("expected a group"),
+	}
+
+	//stopping early leaves the rest untouched
+	let mut trees = token_trees(TokenSlice::new(&lexed));
+	trees.next();
+	assert!(matches!(trees.next(),Some(TokenTree::Group(_))));
+	assert_eq!(trees.rest().input_len(),1);
+}
+
+#[test]
+#[no_mangle]
+fn test_balance_depth() {
+	let depth = 200;
+	let text = format!("def f() {{ {}x{} }}","(".repeat(depth),")".repeat(depth));
+	let balanced = balance(lex_full_text(&text));
+	let errors = balanced.iter().filter_map(|t| t.error.as_deref()).collect::<Vec<_>>();
+	assert!(matches!(&errors[..],[UserSideError::NestedTooDeep(span)] if span.location_offset()==10+MAX_DEPTH-1),"{:?}",errors);
+
+	//every group the parser sees is within the limit so this does not overflow.
+	//the flattened delimiters are left to the parser to complain about
+	let (_,node) = crate::parse::parse(TokenSlice::new(&balanced)).unwrap();
+	let errors = crate::diagnostics::collect_errors(&node);
+	assert!(matches!(errors.first(),Some(UserSideError::NestedTooDeep(_))),"{:?}",errors);
+}
+
+#[test]
+#[no_mangle]
+fn test_balance_then_parse() {
+	use crate::parse::parse;
+	use crate::diagnostics::collect_errors;
+	use crate::ast::GrammerNodeBase;
+
+	let text = "
+def f(x) {
+	y = g(x;
+	y + 1
+def h() { 2 ) }
+";
+	let balanced = balance(lex_full_text(text));
+	let (_,node) = parse(TokenSlice::new(&balanced)).unwrap();
+	let functions = match &node.base {
+		GrammerNodeBase::Sequence(nodes) => nodes,
+		_ => unreachable!("expected a sequence"),
+	};
+	assert!(functions.len()==2);
+
+	let errors = collect_errors(&functions[0]);
+	assert!(matches!(&errors[..],
+		[UserSideError::UnclosedPar(brace,brace_end),UserSideError::UnclosedPar(paren,paren_end)]
+		if *brace.fragment()=="{" && *brace_end.fragment()=="1" && *paren.fragment()=="(" && *paren_end.fragment()=="x"
+	),"{:?}",errors);
+
+	let errors = collect_errors(&functions[1]);
+	assert!(matches!(&errors[..],[UserSideError::ExtraPar(span)] if *span.fragment()==")"),"{:?}",errors);
+}
+